@@ -0,0 +1,98 @@
+//! Catch-all for fields that Slack sends but the types in this crate don't know about
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref REPORTED: Mutex<HashSet<(&'static str, String)>> = Mutex::new(HashSet::new());
+}
+
+/// The unknown fields of a `T`, which is only used to say where they came from
+pub struct Extra<T> {
+    pub fields: HashMap<String, Value>,
+    _type: PhantomData<T>,
+}
+
+impl<T> Extra<T> {
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+// Derives would require T to implement these too
+impl<T> Clone for Extra<T> {
+    fn clone(&self) -> Self {
+        Extra {
+            fields: self.fields.clone(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for Extra<T> {
+    fn default() -> Self {
+        Extra {
+            fields: HashMap::new(),
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T> ::std::fmt::Debug for Extra<T> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        self.fields.fmt(f)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Extra<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let fields = HashMap::<String, Value>::deserialize(deserializer)?;
+        if !fields.is_empty() {
+            let type_name = ::std::any::type_name::<T>();
+            // Every message would otherwise log the same handful of fields. Only the name is
+            // logged, the value could be someone's message.
+            if let Ok(mut reported) = REPORTED.lock() {
+                for name in fields.keys() {
+                    if reported.insert((type_name, name.clone())) {
+                        warn!("Unknown field {} in {}", name, type_name);
+                    }
+                }
+            }
+        }
+        Ok(Extra {
+            fields,
+            _type: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json;
+
+    #[derive(Debug, Deserialize)]
+    struct Known {
+        a: u32,
+        #[serde(flatten)]
+        extra: ::Extra<Known>,
+    }
+
+    #[test]
+    fn unknown_fields_are_collected() {
+        let known: Known = serde_json::from_str(r#"{"a": 1, "b": [2], "c": null}"#).unwrap();
+        assert_eq!(known.a, 1);
+        assert_eq!(known.extra.fields.len(), 2);
+        assert_eq!(known.extra.fields["b"], ::serde_json::Value::Array(vec![2.into()]));
+    }
+
+    #[test]
+    fn no_unknown_fields() {
+        let known: Known = serde_json::from_str(r#"{"a": 1}"#).unwrap();
+        assert!(known.extra.is_empty());
+    }
+}
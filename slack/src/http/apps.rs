@@ -0,0 +1,14 @@
+/// Generate a temporary Socket Mode WebSocket URL that your app can connect to in order to receive
+/// events and interactive payloads over.
+///
+/// Wraps https://api.slack.com/methods/apps.connections.open
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct ConnectionsOpenResponse {
+    ok: bool,
+    pub url: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConnectionsOpenResponse>,
+}
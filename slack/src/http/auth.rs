@@ -10,10 +10,13 @@ pub struct RevokeRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RevokeResponse {
     ok: bool,
     pub revoked: bool,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RevokeResponse>,
 }
 
 /// Checks authentication & identity.
@@ -21,7 +24,7 @@ pub struct RevokeResponse {
 /// Wraps https://api.slack.com/methods/auth.test
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TestResponse {
     ok: bool,
     pub team: String,
@@ -29,4 +32,7 @@ pub struct TestResponse {
     pub url: String,
     pub user: String,
     pub user_id: ::UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<TestResponse>,
 }
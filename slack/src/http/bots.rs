@@ -10,26 +10,35 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub bot: Option<InfoResponseBot>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponseBot {
     pub app_id: ::AppId,
     pub deleted: bool,
     pub icons: InfoResponseBotIcons,
     pub id: ::BotId,
     pub name: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponseBot>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponseBotIcons {
     pub image_36: Option<String>,
     pub image_48: Option<String>,
     pub image_72: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponseBotIcons>,
 }
@@ -25,10 +25,13 @@ pub struct CreateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CreateResponse {
     ok: bool,
     pub channel: Channel,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CreateResponse>,
 }
 
 /// Fetches history of messages and events from a channel.
@@ -57,13 +60,16 @@ pub struct HistoryRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct HistoryResponse {
     ok: bool,
     pub has_more: Option<bool>,
     pub latest: Option<Timestamp>,
     pub messages: Vec<Message>,
     pub is_limited: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<HistoryResponse>,
 }
 
 /// Gets information about a channel.
@@ -79,10 +85,13 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub channel: Channel,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Invites a user to a channel.
@@ -98,10 +107,13 @@ pub struct InviteRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InviteResponse {
     ok: bool,
     pub channel: Channel,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InviteResponse>,
 }
 
 /// Joins a channel, creating it if needed.
@@ -118,11 +130,14 @@ pub struct JoinRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct JoinResponse {
     ok: bool,
     pub channel: Channel, //TODO: This contains different attributes depending on already_in_channel
     pub already_in_channel: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<JoinResponse>,
 }
 
 /// Removes a user from a channel.
@@ -148,10 +163,13 @@ pub struct LeaveRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct LeaveResponse {
     ok: bool,
     not_in_channel: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<LeaveResponse>,
 }
 
 /// Lists all channels in a Slack team.
@@ -173,11 +191,14 @@ pub struct ListRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub channels: Vec<Channel>,
     pub response_metadata: Option<Paging>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Sets the read cursor in a channel.
@@ -208,10 +229,13 @@ pub struct RenameRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RenameResponse {
     ok: bool,
     pub channel: Channel,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RenameResponse>,
 }
 
 /// Retrieve a thread of messages posted to a channel
@@ -227,11 +251,14 @@ pub struct RepliesRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RepliesResponse {
     ok: bool,
     pub has_more: bool,
     pub messages: Vec<Message>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RepliesResponse>,
 }
 
 /// Sets the purpose for a channel.
@@ -247,10 +274,13 @@ pub struct SetPurposeRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetPurposeResponse {
     ok: bool,
     pub purpose: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetPurposeResponse>,
 }
 
 /// Sets the topic for a channel.
@@ -266,10 +296,13 @@ pub struct SetTopicRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetTopicResponse {
     ok: bool,
     pub topic: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetTopicResponse>,
 }
 
 /// Unarchives a channel.
@@ -19,11 +19,14 @@ pub struct DeleteRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct DeleteResponse {
     ok: bool,
     pub channel: ::ChannelId,
     pub ts: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<DeleteResponse>,
 }
 
 /// Share a me message into a channel.
@@ -39,11 +42,14 @@ pub struct MeMessageRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MeMessageResponse {
     ok: bool,
     pub channel: Option<String>,
     pub ts: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MeMessageResponse>,
 }
 
 /// Sends a message to a channel.
@@ -95,7 +101,7 @@ pub struct PostMessageRequest<'a> {
 }
 
 #[derive(Clone, Copy, Debug, Serialize)]
-#[serde(rename = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum ParseMode {
     None,
     Full,
@@ -109,12 +115,15 @@ impl Default for ParseMode {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PostMessageResponse {
     ok: bool,
     pub channel: ::ConversationId,
     pub message: Message,
     pub ts: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<PostMessageResponse>,
 }
 
 /// Unfurl a URL that a user posted
@@ -167,10 +176,13 @@ pub struct UpdateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UpdateResponse {
     ok: bool,
     pub channel: String,
     pub text: String,
     pub ts: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UpdateResponse>,
 }
@@ -23,11 +23,14 @@ pub struct CloseRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CloseResponse {
     ok: bool,
     no_op: Option<bool>,
     already_closed: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CloseResponse>,
 }
 
 /// Initiates a public or private channel-based conversation
@@ -50,10 +53,13 @@ pub struct CreateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CreateResponse {
     ok: bool,
     pub channel: Option<Conversation>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CreateResponse>,
 }
 
 /// Fetches a conversation's history of messages and events.
@@ -87,7 +93,7 @@ pub struct HistoryRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct HistoryResponse {
     ok: bool,
     pub messages: Vec<Message>,
@@ -95,12 +101,18 @@ pub struct HistoryResponse {
     pub pin_count: u32,
     pub response_metadata: Option<ResponseMetadata>,
     pub is_limited: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<HistoryResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ResponseMetadata {
     next_cursor: Cursor,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ResponseMetadata>,
 }
 
 /// Retrieve information about a conversation.
@@ -117,10 +129,13 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub channel: ConversationInfo,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Invites users to a channel.
@@ -138,10 +153,13 @@ pub struct InviteRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InviteResponse {
     ok: bool,
     pub channel: Conversation,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InviteResponse>,
 }
 
 /// Joins an existing conversation.
@@ -155,11 +173,14 @@ pub struct JoinRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct JoinResponse {
     pub channel: Conversation,
     pub warning: Option<String>,
     pub response_metadata: Option<JoinResponseMetadata>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<JoinResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -236,11 +257,14 @@ impl ::std::fmt::Display for ChannelType {
 
 // TODO: This returns a _partial_ conversation object, per the slack docs
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub channels: Vec<Conversation>,
     pub response_metadata: Option<ResponseMetadata>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Retrieve members of a conversation.
@@ -262,11 +286,14 @@ pub struct MembersRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MembersResponse {
     ok: bool,
     pub members: Vec<::UserId>,
     pub response_metadata: Option<ResponseMetadata>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MembersResponse>,
 }
 
 // TODO: Undocumented method
@@ -295,10 +322,13 @@ pub struct RenameRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RenameResponse {
     ok: bool,
     pub channel: Conversation,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RenameResponse>,
 }
 
 /// Retrieve a thread of messages posted to a conversation
@@ -334,13 +364,16 @@ pub struct RepliesRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RepliesResponse {
     ok: bool,
     #[serde(default)]
     pub messages: Vec<Message>,
     pub has_more: Option<bool>,
     pub response_metadata: Option<ResponseMetadata>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RepliesResponse>,
 }
 
 /// Sets the purpose for a conversation.
@@ -356,10 +389,13 @@ pub struct SetPurposeRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetPurposeResponse {
     ok: bool,
     pub purpose: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetPurposeResponse>,
 }
 
 /// Sets the topic for a conversation
@@ -375,10 +411,13 @@ pub struct SetTopicRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetTopicResponse {
     ok: bool,
     pub topic: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetTopicResponse>,
 }
 
 /// Reverses conversation archival.
@@ -419,15 +458,18 @@ pub struct ConversationsRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConversationsResponse {
     ok: bool,
     pub channels: Vec<Conversation>,
     pub response_metadata: Option<ResponseMetadata>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConversationsResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[serde(untagged)]
 pub enum Conversation {
     Channel {
@@ -495,21 +537,27 @@ pub enum Conversation {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConversationPurpose {
     #[serde(deserialize_with = "deserialize_userid_or_empty")]
     pub creator: Option<UserId>,
     pub last_set: Timestamp,
     pub value: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConversationPurpose>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConversationTopic {
     #[serde(deserialize_with = "deserialize_userid_or_empty")]
     pub creator: Option<UserId>,
     pub last_set: Timestamp,
     pub value: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConversationTopic>,
 }
 
 fn deserialize_userid_or_empty<'de, D>(deserializer: D) -> Result<Option<UserId>, D::Error>
@@ -537,7 +585,7 @@ where
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[serde(untagged)]
 pub enum ConversationInfo {
     Channel {
@@ -567,6 +615,10 @@ pub enum ConversationInfo {
         purpose: ConversationPurpose,
         shared_team_ids: Vec<TeamId>,
         topic: ConversationTopic,
+        /// Only given to user tokens
+        unread_count: Option<u32>,
+        /// Leaves out messages from muted channels and threads
+        unread_count_display: Option<u32>,
         unlinked: u32,
     },
     Group {
@@ -593,6 +645,10 @@ pub enum ConversationInfo {
         purpose: ConversationPurpose,
         shared_team_ids: Vec<TeamId>,
         topic: ConversationTopic,
+        /// Only given to user tokens
+        unread_count: Option<u32>,
+        /// Leaves out messages from muted channels and threads
+        unread_count_display: Option<u32>,
         unlinked: u32,
     },
     OpenDirectMessage {
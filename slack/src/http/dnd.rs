@@ -11,13 +11,16 @@ use rtm::Team;
 /// Wraps https://api.slack.com/methods/dnd.endSnooze
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct EndSnoozeResponse {
     ok: bool,
     pub dnd_enabled: Option<bool>,
     pub next_dnd_end_ts: Option<f32>,
     pub next_dnd_start_ts: Option<f32>,
     pub snooze_enabled: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<EndSnoozeResponse>,
 }
 
 /// Retrieves a user's current Do Not Disturb status.
@@ -32,7 +35,7 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub dnd_enabled: Option<bool>,
@@ -41,6 +44,9 @@ pub struct InfoResponse {
     pub snooze_enabled: Option<bool>,
     pub snooze_endtime: Option<f32>,
     pub snooze_remaining: Option<f32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Turns on Do Not Disturb mode for the current user, or changes its duration.
@@ -54,12 +60,15 @@ pub struct SetSnoozeRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetSnoozeResponse {
     ok: bool,
     pub snooze_enabled: Option<bool>,
     pub snooze_endtime: Option<f32>,
     pub snooze_remaining: Option<f32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetSnoozeResponse>,
 }
 
 /// Retrieves the Do Not Disturb status for users on a team.
@@ -75,8 +84,11 @@ pub struct TeamInfoRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TeamInfoResponse {
     ok: bool,
     pub team: Team,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<TeamInfoResponse>,
 }
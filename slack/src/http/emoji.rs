@@ -5,9 +5,12 @@ use std::collections::HashMap;
 /// Wraps https://api.slack.com/methods/emoji.list
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub emoji: Option<HashMap<String, String>>,
     cache_ts: Option<::Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
@@ -29,12 +29,15 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub comments: Vec<FileComment>,
     pub file: File,
     pub paging: Paging,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Lists & filters team files.
@@ -97,11 +100,14 @@ impl Default for FileType {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub files: Option<Vec<File>>,
     pub paging: Option<Paging>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Revokes public/external sharing access for a file
@@ -115,10 +121,13 @@ pub struct RevokePublicURLRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RevokePublicURLResponse {
     ok: bool,
     pub file: File,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RevokePublicURLResponse>,
 }
 
 /// Enables a file for public/external sharing.
@@ -132,8 +141,11 @@ pub struct SharedPublicURLRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SharedPublicURLResponse {
     ok: bool,
     pub file: File,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SharedPublicURLResponse>,
 }
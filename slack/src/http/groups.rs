@@ -37,10 +37,13 @@ pub struct CreateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CreateResponse {
     ok: bool,
     pub group: Option<Group>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CreateResponse>,
 }
 
 /// Clones and archives a private channel.
@@ -54,10 +57,13 @@ pub struct CreateChildRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CreateChildResponse {
     ok: bool,
     pub group: Option<Group>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CreateChildResponse>,
 }
 
 /// Fetches history of messages and events from a private channel.
@@ -86,13 +92,16 @@ pub struct HistoryRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct HistoryResponse {
     ok: bool,
     pub has_more: bool,
     pub latest: Option<Timestamp>,
     pub messages: Vec<Message>,
     pub is_limited: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<HistoryResponse>,
 }
 
 /// Gets information about a private channel.
@@ -106,10 +115,13 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub group: Group,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Invites a user to a private channel.
@@ -125,10 +137,13 @@ pub struct InviteRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InviteResponse {
     ok: bool,
     pub group: Option<Group>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InviteResponse>,
 }
 
 /// Removes a user from a private channel.
@@ -165,10 +180,13 @@ pub struct ListRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub groups: Vec<Group>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Sets the read cursor in a private channel.
@@ -209,19 +227,25 @@ pub struct RenameRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RenameResponse {
     ok: bool,
     pub channel: Option<RenameResponseGroup>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RenameResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RenameResponseGroup {
     pub created: Option<Timestamp>,
     pub id: Option<::GroupId>,
     pub is_group: Option<bool>,
     pub name: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RenameResponseGroup>,
 }
 
 /// Retrieve a thread of messages posted to a private channel
@@ -237,11 +261,14 @@ pub struct RepliesRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RepliesResponse {
     ok: bool,
     pub messages: Option<Vec<Message>>,
     pub thread_info: Option<ThreadInfo>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RepliesResponse>,
 }
 
 /// Sets the purpose for a private channel.
@@ -257,10 +284,13 @@ pub struct SetPurposeRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetPurposeResponse {
     ok: bool,
     pub purpose: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetPurposeResponse>,
 }
 
 /// Sets the topic for a private channel.
@@ -276,10 +306,13 @@ pub struct SetTopicRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetTopicResponse {
     ok: bool,
     pub topic: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetTopicResponse>,
 }
 
 /// Unarchives a private channel.
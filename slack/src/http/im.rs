@@ -39,7 +39,7 @@ pub struct HistoryRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct HistoryResponse {
     ok: bool,
     pub has_more: Option<bool>,
@@ -47,6 +47,9 @@ pub struct HistoryResponse {
     #[serde(default)]
     pub messages: Vec<Message>,
     pub is_limited: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<HistoryResponse>,
 }
 
 /// Lists direct message channels for the calling user.
@@ -64,11 +67,14 @@ pub struct ListRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     #[serde(default)]
     pub ims: Vec<Im>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Sets the read cursor in a direct message channel.
@@ -115,9 +121,12 @@ pub struct RepliesRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RepliesResponse {
     ok: bool,
     pub messages: Option<Vec<Message>>,
     pub thread_info: Option<ThreadInfo>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RepliesResponse>,
 }
@@ -7,6 +7,7 @@ pub struct SlackError {
 }
 
 pub mod api;
+pub mod apps;
 pub mod auth;
 pub mod bots;
 pub mod channels;
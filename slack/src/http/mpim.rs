@@ -39,12 +39,15 @@ pub struct HistoryRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct HistoryResponse {
     ok: bool,
     pub has_more: Option<bool>,
     pub latest: Option<String>,
     pub messages: Option<Vec<Message>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<HistoryResponse>,
 }
 
 /// Lists multiparty direct message channels for the calling user.
@@ -81,10 +84,13 @@ pub struct OpenRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct OpenResponse {
     ok: bool,
     pub group: Option<Mpim>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<OpenResponse>,
 }
 
 /// Retrieve a thread of messages posted to a direct message conversation from a multiparty direct message.
@@ -100,9 +106,12 @@ pub struct RepliesRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct RepliesResponse {
     ok: bool,
     pub messages: Option<Vec<Message>>,
     pub thread_info: Option<ThreadInfo>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<RepliesResponse>,
 }
@@ -16,8 +16,75 @@ pub struct AccessRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AccessResponse {
     pub access_token: Option<String>,
     pub scope: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AccessResponse>,
+}
+
+/// Exchanges a temporary OAuth verifier code for an access token, for apps using granular scopes.
+///
+/// Wraps https://api.slack.com/methods/oauth.v2.access
+
+#[derive(Clone, Debug, Serialize, new)]
+pub struct V2AccessRequest<'a> {
+    /// Issued when you created your application.
+    pub client_id: &'a str,
+    /// Issued when you created your application.
+    pub client_secret: &'a str,
+    /// The code param returned via the OAuth callback.
+    pub code: &'a str,
+    /// This must match the originally submitted URI (if one was sent).
+    #[new(default)]
+    pub redirect_uri: Option<&'a str>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct V2AccessResponse {
+    ok: bool,
+    /// The bot token, if any bot scopes were requested
+    pub access_token: Option<String>,
+    pub token_type: Option<String>,
+    pub scope: Option<String>,
+    pub bot_user_id: Option<String>,
+    pub app_id: String,
+    pub team: V2AccessResponseTeam,
+    pub enterprise: Option<V2AccessResponseTeam>,
+    pub is_enterprise_install: Option<bool>,
+    pub authed_user: V2AccessResponseAuthedUser,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+    pub incoming_webhook: Option<::serde_json::Value>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<V2AccessResponse>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct V2AccessResponseTeam {
+    pub id: String,
+    pub name: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<V2AccessResponseTeam>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct V2AccessResponseAuthedUser {
+    pub id: String,
+    /// The user token, if any user scopes were requested
+    pub access_token: Option<String>,
+    pub token_type: Option<String>,
+    pub scope: Option<String>,
+    pub refresh_token: Option<String>,
+    pub expires_in: Option<u64>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<V2AccessResponseAuthedUser>,
 }
@@ -14,30 +14,39 @@ pub struct ConnectRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConnectResponse {
     ok: bool,
     #[serde(rename = "self")]
     pub slf: ConnectResponseSelf,
     pub team: ConnectResponseTeam,
     pub url: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConnectResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConnectResponseSelf {
     pub id: String,
     pub name: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConnectResponseSelf>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ConnectResponseTeam {
     pub domain: String,
     pub enterprise_id: Option<String>,
     pub enterprise_name: Option<String>,
     pub id: ::TeamId,
     pub name: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ConnectResponseTeam>,
 }
 
 /// Starts a Real Time Messaging session.
@@ -64,7 +73,7 @@ pub struct StartRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct StartResponse {
     ok: bool,
     pub bots: Option<Vec<Bot>>,
@@ -87,21 +96,30 @@ pub struct StartResponse {
     pub cache_ts_version: Option<String>,
     pub dnd: Option<Dnd>,
     pub subteams: Option<Subteams>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<StartResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Dnd {
     pub dnd_enabled: bool,
     pub next_dnd_end_ts: Timestamp,
     pub next_dnd_start_ts: Timestamp,
     pub snooze_enabled: bool,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Dnd>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Subteams {
     pub all: Option<Vec<Team>>,
     #[serde(rename = "self")]
     pub slf: Option<Vec<String>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Subteams>,
 }
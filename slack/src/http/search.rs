@@ -38,26 +38,35 @@ pub struct AllRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AllResponse {
     ok: bool,
     pub files: Option<AllResponseFiles>,
     pub messages: Option<AllResponseMessages>,
     pub query: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AllResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AllResponseFiles {
     pub matches: Vec<File>,
     pub paging: Paging,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AllResponseFiles>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AllResponseMessages {
     pub matches: Vec<Message>,
     pub paging: Paging,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AllResponseMessages>,
 }
 
 /// Searches for files matching a query.
@@ -87,19 +96,25 @@ pub struct FilesRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct FilesResponse {
     ok: bool,
     pub files: Option<FilesResponseFiles>,
     pub query: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<FilesResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct FilesResponseFiles {
     pub matches: Option<Vec<File>>,
     pub paging: Option<Paging>,
     pub total: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<FilesResponseFiles>,
 }
 
 /// Searches for messages matching a query.
@@ -128,17 +143,23 @@ pub struct MessagesRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessagesResponse {
     ok: bool,
     pub messages: Option<MessagesResponseMessages>,
     pub query: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessagesResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessagesResponseMessages {
     pub matches: Option<Vec<Message>>,
     pub paging: Option<Paging>,
     pub total: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessagesResponseMessages>,
 }
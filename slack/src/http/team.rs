@@ -20,15 +20,18 @@ pub struct AccessLogsRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AccessLogsResponse {
     ok: bool,
     pub logins: Option<Vec<AccessLogsResponseLogin>>,
     pub paging: Option<Paging>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AccessLogsResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AccessLogsResponseLogin {
     pub count: Option<u32>,
     pub country: Option<String>,
@@ -40,6 +43,9 @@ pub struct AccessLogsResponseLogin {
     pub user_agent: Option<String>,
     pub user_id: Option<String>,
     pub username: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AccessLogsResponseLogin>,
 }
 
 /// Gets billable users information for the current team.
@@ -54,10 +60,13 @@ pub struct BillableInfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct BillableInfoResponse {
     ok: bool,
     pub billable_info: HashMap<String, bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<BillableInfoResponse>,
 }
 
 /// Gets information about the current team.
@@ -65,10 +74,13 @@ pub struct BillableInfoResponse {
 /// Wraps https://api.slack.com/methods/team.info
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub team: Option<Team>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Gets the integration logs for the current team.
@@ -98,15 +110,18 @@ pub struct IntegrationLogsRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct IntegrationLogsResponse {
     ok: bool,
     pub logs: Option<Vec<IntegrationLogsResponseLog>>,
     pub paging: Option<Paging>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<IntegrationLogsResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct IntegrationLogsResponseLog {
     pub app_id: Option<String>,
     pub app_type: Option<String>,
@@ -119,4 +134,7 @@ pub struct IntegrationLogsResponseLog {
     pub service_type: Option<String>,
     pub user_id: Option<String>,
     pub user_name: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<IntegrationLogsResponseLog>,
 }
@@ -20,20 +20,26 @@ pub enum Visibility {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GetResponse {
     ok: bool,
     pub profile: Option<GetResponseProfile>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GetResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GetResponseProfile {
     pub fields: Option<Vec<GetResponseProfileField>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GetResponseProfile>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GetResponseProfileField {
     pub hint: Option<String>,
     pub id: Option<String>,
@@ -44,4 +50,7 @@ pub struct GetResponseProfileField {
     pub possible_values: Option<Vec<String>>,
     #[serde(rename = "type")]
     pub ty: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GetResponseProfileField>,
 }
@@ -25,10 +25,13 @@ pub struct CreateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct CreateResponse {
     ok: bool,
     pub usergroup: Option<Usergroup>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<CreateResponse>,
 }
 
 /// Disable an existing User Group
@@ -45,10 +48,13 @@ pub struct DisableRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct DisableResponse {
     ok: bool,
     pub usergroup: Option<Usergroup>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<DisableResponse>,
 }
 
 /// Enable a User Group
@@ -65,10 +71,13 @@ pub struct EnableRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct EnableResponse {
     ok: bool,
     pub usergroup: Option<Usergroup>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<EnableResponse>,
 }
 
 /// List all User Groups for a team
@@ -89,10 +98,13 @@ pub struct ListRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub usergroups: Option<Vec<Usergroup>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Update an existing User Group
@@ -122,8 +134,11 @@ pub struct UpdateRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UpdateResponse {
     ok: bool,
     pub usergroup: Option<Usergroup>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UpdateResponse>,
 }
@@ -20,10 +20,13 @@ pub struct GetPresenceRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GetPresenceResponse {
     ok: bool,
     pub presence: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GetPresenceResponse>,
 }
 
 /// Get a user's identity.
@@ -31,11 +34,14 @@ pub struct GetPresenceResponse {
 /// Wraps https://api.slack.com/methods/users.identity
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct IdentityResponse {
     ok: bool,
     pub team: Option<Team>,
     pub user: Option<User>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<IdentityResponse>,
 }
 
 /// Gets information about a user.
@@ -49,10 +55,13 @@ pub struct InfoRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct InfoResponse {
     ok: bool,
     pub user: Option<User>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<InfoResponse>,
 }
 
 /// Lists all users in a Slack team.
@@ -79,13 +88,16 @@ pub struct ListRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ListResponse {
     ok: bool,
     pub members: Vec<User>,
     pub cache_ts: Option<Timestamp>,
     pub response_metadata: Option<Paging>,
     pub is_limited: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ListResponse>,
 }
 
 /// Gets a users's preferences
@@ -93,16 +105,22 @@ pub struct ListResponse {
 /// Wraps https://api.slack.com/methods/users.prefs.get
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PrefsResponse {
     ok: bool,
     pub prefs: UserPrefs,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<PrefsResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UserPrefs {
     muted_channels: Vec<ChannelId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UserPrefs>,
 }
 
 /// Marks a user as active.
@@ -127,7 +145,7 @@ pub enum Presence {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct User {
     pub color: Option<String>,
     pub deleted: bool,
@@ -149,10 +167,13 @@ pub struct User {
     pub tz_label: Option<String>,
     pub tz_offset: Option<i64>,
     pub updated: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<User>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UserProfile {
     pub always_active: Option<bool>,
     pub bot_id: Option<BotId>,
@@ -183,4 +204,7 @@ pub struct UserProfile {
     pub team: TeamId,
     pub title: String,
     pub fields: Option<()>, // No idea what goes here
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UserProfile>,
 }
@@ -15,10 +15,13 @@ pub struct GetRequest {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GetResponse {
     ok: bool,
     pub profile: Option<UserProfile>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GetResponse>,
 }
 
 /// Set the profile information for a user.
@@ -42,8 +45,11 @@ pub struct SetRequest<'a> {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct SetResponse {
     ok: bool,
     pub profile: Option<UserProfile>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<SetResponse>,
 }
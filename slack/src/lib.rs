@@ -22,12 +22,16 @@ extern crate uuid;
 #[macro_use]
 extern crate derive_new;
 
-#[cfg(test)]
+#[cfg(any(test, feature = "lenient"))]
 #[macro_use]
 extern crate lazy_static;
+#[cfg(feature = "lenient")]
+#[macro_use]
+extern crate log;
 
 pub mod http;
 pub mod rtm;
+pub mod socket_mode;
 
 mod timestamp;
 pub use timestamp::Timestamp;
@@ -35,6 +39,11 @@ pub use timestamp::Timestamp;
 mod id;
 pub use id::*;
 
+#[cfg(feature = "lenient")]
+mod extra;
+#[cfg(feature = "lenient")]
+pub use extra::Extra;
+
 fn serialize_comma_separated<T, S>(items: &[T], serializer: S) -> Result<S::Ok, S::Error>
 where
     S: ::serde::Serializer,
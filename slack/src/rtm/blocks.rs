@@ -0,0 +1,283 @@
+//! Block Kit, the layout format newer Slack apps use for message content
+//!
+//! See https://api.slack.com/reference/block-kit/blocks
+
+use id::*;
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum Block {
+    Section {
+        block_id: Option<String>,
+        text: Option<TextObject>,
+        fields: Option<Vec<TextObject>>,
+        accessory: Option<BlockElement>,
+    },
+    Context {
+        block_id: Option<String>,
+        elements: Vec<ContextElement>,
+    },
+    Divider {
+        block_id: Option<String>,
+    },
+    Header {
+        block_id: Option<String>,
+        text: TextObject,
+    },
+    Actions {
+        block_id: Option<String>,
+        elements: Vec<BlockElement>,
+    },
+    Image {
+        block_id: Option<String>,
+        image_url: String,
+        alt_text: String,
+        title: Option<TextObject>,
+        image_width: Option<u32>,
+        image_height: Option<u32>,
+        image_bytes: Option<u64>,
+        fallback: Option<String>,
+        is_animated: Option<bool>,
+    },
+    RichText {
+        block_id: Option<String>,
+        elements: Vec<RichTextElement>,
+    },
+    /// Block types that are newer than this crate, or only used in modals
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum TextObject {
+    PlainText { text: String, emoji: Option<bool> },
+    Mrkdwn { text: String, verbatim: Option<bool> },
+}
+
+impl TextObject {
+    pub fn text(&self) -> &str {
+        match self {
+            TextObject::PlainText { text, .. } | TextObject::Mrkdwn { text, .. } => text,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum ContextElement {
+    PlainText { text: String, emoji: Option<bool> },
+    Mrkdwn { text: String, verbatim: Option<bool> },
+    Image { image_url: String, alt_text: String },
+}
+
+/// Interactive elements, and images used as a section's accessory
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum BlockElement {
+    Button {
+        text: TextObject,
+        action_id: Option<String>,
+        url: Option<String>,
+        value: Option<String>,
+        style: Option<String>,
+        confirm: Option<::serde_json::Value>,
+        accessibility_label: Option<String>,
+    },
+    Image {
+        image_url: String,
+        alt_text: String,
+    },
+    /// Selects, date pickers and the like, which a terminal can't do much with
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum RichTextElement {
+    RichTextSection {
+        elements: Vec<RichTextInline>,
+    },
+    RichTextList {
+        style: RichTextListStyle,
+        elements: Vec<RichTextElement>,
+        indent: Option<u32>,
+        offset: Option<u32>,
+        border: Option<u32>,
+    },
+    RichTextPreformatted {
+        elements: Vec<RichTextInline>,
+        border: Option<u32>,
+    },
+    RichTextQuote {
+        elements: Vec<RichTextInline>,
+        border: Option<u32>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RichTextListStyle {
+    Bullet,
+    Ordered,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+pub enum RichTextInline {
+    Text {
+        text: String,
+        style: Option<RichTextStyle>,
+    },
+    Link {
+        url: String,
+        text: Option<String>,
+        style: Option<RichTextStyle>,
+        #[serde(rename = "unsafe")]
+        is_unsafe: Option<bool>,
+    },
+    User {
+        user_id: UserId,
+        style: Option<RichTextStyle>,
+    },
+    Channel {
+        channel_id: ConversationId,
+        style: Option<RichTextStyle>,
+    },
+    Usergroup {
+        usergroup_id: String,
+        style: Option<RichTextStyle>,
+    },
+    Emoji {
+        name: String,
+        unicode: Option<String>,
+        skin_tone: Option<u8>,
+        style: Option<RichTextStyle>,
+    },
+    Broadcast {
+        range: String,
+        style: Option<RichTextStyle>,
+    },
+    Color {
+        value: String,
+        style: Option<RichTextStyle>,
+    },
+    Date {
+        timestamp: i64,
+        format: String,
+        fallback: Option<String>,
+        url: Option<String>,
+        style: Option<RichTextStyle>,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct RichTextStyle {
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub strike: bool,
+    #[serde(default)]
+    pub code: bool,
+    #[serde(default)]
+    pub underline: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json;
+
+    #[test]
+    fn rich_text_deserialize() {
+        let blocks: Vec<Block> = serde_json::from_str(
+            r#"[{
+                "type": "rich_text",
+                "block_id": "a1b",
+                "elements": [
+                    {"type": "rich_text_section", "elements": [
+                        {"type": "text", "text": "hello "},
+                        {"type": "text", "text": "world", "style": {"bold": true}},
+                        {"type": "user", "user_id": "U0G9QF9C6"},
+                        {"type": "emoji", "name": "wave", "unicode": "1f44b"}
+                    ]},
+                    {"type": "rich_text_list", "style": "bullet", "indent": 0, "elements": [
+                        {"type": "rich_text_section", "elements": [{"type": "text", "text": "one"}]}
+                    ]},
+                    {"type": "rich_text_preformatted", "border": 0, "elements": [
+                        {"type": "text", "text": "let x = 1;"}
+                    ]}
+                ]
+            }]"#,
+        ).unwrap();
+        match blocks[0] {
+            Block::RichText { ref elements, .. } => {
+                assert_eq!(elements.len(), 3);
+                match elements[0] {
+                    RichTextElement::RichTextSection { ref elements } => match elements[1] {
+                        RichTextInline::Text {
+                            style: Some(style), ..
+                        } => assert!(style.bold && !style.italic),
+                        ref other => panic!("{:?}", other),
+                    },
+                    ref other => panic!("{:?}", other),
+                }
+            }
+            ref other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn layout_blocks_deserialize() {
+        let blocks: Vec<Block> = serde_json::from_str(
+            r#"[
+                {"type": "header", "text": {"type": "plain_text", "text": "Deploy", "emoji": true}},
+                {"type": "section", "text": {"type": "mrkdwn", "text": "*done*"},
+                 "fields": [{"type": "mrkdwn", "text": "a"}],
+                 "accessory": {"type": "button", "text": {"type": "plain_text", "text": "Open"}, "url": "https://example.com"}},
+                {"type": "divider"},
+                {"type": "context", "elements": [{"type": "mrkdwn", "text": "by bot"}]},
+                {"type": "actions", "elements": [{"type": "static_select", "action_id": "x", "options": []}]}
+            ]"#,
+        ).unwrap();
+        assert_eq!(blocks.len(), 5);
+        match blocks[4] {
+            Block::Actions { ref elements, .. } => match elements[0] {
+                BlockElement::Unknown => {}
+                ref other => panic!("{:?}", other),
+            },
+            ref other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_blocks_deserialize() {
+        let blocks: Vec<Block> =
+            serde_json::from_str(r#"[{"type": "video", "title": {"type": "plain_text", "text": "x"}}]"#)
+                .unwrap();
+        match blocks[0] {
+            Block::Unknown => {}
+            ref other => panic!("{:?}", other),
+        }
+    }
+}
@@ -39,6 +39,10 @@ pub enum Event {
         channel: ChannelId,
         event_ts: Timestamp,
     },
+    ChannelCreated {
+        channel: Channel,
+        event_ts: Option<Timestamp>,
+    },
     ChannelMarked {
         channel: ChannelId,
         ts: Timestamp,
@@ -182,7 +186,8 @@ pub enum Event {
         channel_type: ChannelType,
         team: TeamId,
         event_ts: Timestamp,
-        ts: Timestamp,
+        // Not sent by the Events API
+        ts: Option<Timestamp>,
     },
     MemberLeftChannel {
         user: UserId,
@@ -196,6 +201,8 @@ pub enum Event {
         #[serde(flatten)]
         message: Message,
         event_ts: Timestamp,
+        /// Only sent by the Events API
+        channel_type: Option<String>,
     },
     PinAdded {
         user: UserId,
@@ -287,7 +294,7 @@ pub enum Event {
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "subtype")]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum EmojiChanged {
     Add {
         name: String,
@@ -299,7 +306,7 @@ pub enum EmojiChanged {
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub enum Reactable {
     Message {
         channel: ConversationId,
@@ -1,5 +1,5 @@
 use id::*;
-use rtm::{File, FileComment, PinnedInfo, Reaction, UserProfile};
+use rtm::{Block, File, FileComment, PinnedInfo, Reaction, UserProfile};
 use timestamp::Timestamp;
 use uuid::Uuid;
 
@@ -106,7 +106,7 @@ deserialize_internally_tagged! {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ShRoom {
     pub channels: Vec<ConversationId>,
     pub created_by: UserId,
@@ -125,10 +125,13 @@ pub struct ShRoom {
     pub was_accepted: bool,
     pub was_missed: bool,
     pub was_rejected: bool,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ShRoom>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageShRoomCreated {
     pub channel: ConversationId,
     pub room: ShRoom,
@@ -137,11 +140,14 @@ pub struct MessageShRoomCreated {
     pub text: String,
     pub ts: Timestamp,
     pub no_notifications: bool,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageShRoomCreated>,
 }
 
 //TODO: Have only seen this once...
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageTombstone {
     pub edited: Box<Message>,
     pub hidden: bool,
@@ -157,10 +163,13 @@ pub struct MessageTombstone {
     // a conversations.history call
     #[serde(rename = "type")]
     pub ty: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageTombstone>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelMarked {
     pub channel: ConversationId,
     pub ts: Option<Timestamp>,
@@ -170,10 +179,13 @@ pub struct MessageChannelMarked {
     pub num_mentions_display: Option<u32>,
     pub mention_count: Option<u32>,
     pub event_ts: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelMarked>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageBotAdd {
     pub bot_id: Option<BotId>,
     pub bot_link: Option<String>,
@@ -181,10 +193,13 @@ pub struct MessageBotAdd {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageBotAdd>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageBotRemove {
     pub bot_id: Option<BotId>,
     pub bot_link: Option<String>,
@@ -192,11 +207,15 @@ pub struct MessageBotRemove {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageBotRemove>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageBotMessage {
+    pub blocks: Option<Vec<Block>>,
     pub bot_id: Option<BotId>,
     pub icons: Option<MessageBotMessageIcons>,
     pub text: Option<String>,
@@ -215,28 +234,37 @@ pub struct MessageBotMessage {
     pub subscribed: Option<bool>,
     pub thread_ts: Option<Timestamp>,
     pub unread_count: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageBotMessage>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageBotMessageIcons {
     pub image_36: Option<String>,
     pub image_48: Option<String>,
     pub image_72: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageBotMessageIcons>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelArchive {
     pub members: Option<Vec<UserId>>,
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelArchive>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelJoin {
     pub channel: Option<ConversationId>, // Not present when deserializing from history
     pub team: Option<TeamId>,
@@ -247,20 +275,26 @@ pub struct MessageChannelJoin {
     pub user_profile: Option<UserProfile>,
     pub reactions: Option<Vec<Reaction>>,
     pub inviter: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelJoin>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelLeave {
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
     pub reactions: Option<Vec<Reaction>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelLeave>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelName {
     pub name: Option<String>,
     pub old_name: Option<String>,
@@ -268,20 +302,26 @@ pub struct MessageChannelName {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelName>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelPurpose {
     pub purpose: Option<String>,
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelPurpose>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelTopic {
     pub channel: Option<ConversationId>,
     pub team: Option<TeamId>,
@@ -292,19 +332,25 @@ pub struct MessageChannelTopic {
     pub user: Option<UserId>,
     pub user_profie: Option<UserProfile>,
     pub reactions: Option<Vec<Reaction>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelTopic>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageChannelUnarchive {
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageChannelUnarchive>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageFileComment {
     pub comment: Option<FileComment>,
     pub file: Option<File>,
@@ -313,20 +359,26 @@ pub struct MessageFileComment {
     pub ts: Option<Timestamp>,
     pub files: Option<Vec<File>>,
     pub is_intro: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageFileComment>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageFileMention {
     pub file: Option<File>,
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageFileMention>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageFileShare {
     pub channel: Option<ConversationId>,
     pub file: Option<File>,
@@ -337,39 +389,51 @@ pub struct MessageFileShare {
     pub user: Option<UserId>,
     #[serde(default)]
     pub reactions: Vec<Reaction>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageFileShare>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupArchive {
     pub members: Option<Vec<UserId>>,
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupArchive>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupJoin {
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
     pub inviter: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupJoin>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupLeave {
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupLeave>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupName {
     pub name: Option<String>,
     pub old_name: Option<String>,
@@ -377,39 +441,51 @@ pub struct MessageGroupName {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupName>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupPurpose {
     pub purpose: Option<String>,
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupPurpose>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupTopic {
     #[serde(default)]
     pub text: String,
     pub topic: Option<String>,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupTopic>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageGroupUnarchive {
     #[serde(default)]
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageGroupUnarchive>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMeMessage {
     pub channel: Option<ConversationId>,
     #[serde(default)]
@@ -423,24 +499,33 @@ pub struct MessageMeMessage {
     pub replies: Option<Vec<MessageReply>>,
     pub thread_ts: Option<Timestamp>,
     pub edited: Option<EditInfo>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMeMessage>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct EditInfo {
     ts: Timestamp,
     user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<EditInfo>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageReply {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageReply>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChanged {
     pub channel: ConversationId,
     pub event_ts: Option<Timestamp>,
@@ -448,10 +533,13 @@ pub struct MessageMessageChanged {
     pub message: Option<Box<Message>>,
     pub previous_message: Option<Box<Message>>,
     pub ts: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChanged>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedMessage {
     pub bot_id: Option<BotId>,
     pub edited: Option<MessageMessageChangedMessageEdited>,
@@ -469,24 +557,33 @@ pub struct MessageMessageChangedMessage {
     pub client_msg_id: Option<Uuid>,
     #[serde(rename = "type")]
     ty: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedMessage>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedMessageEdited {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedMessageEdited>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedMessageReply {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedMessageReply>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedPreviousMessage {
     pub bot_id: Option<BotId>,
     pub edited: Option<MessageMessageChangedPreviousMessageEdited>,
@@ -504,24 +601,33 @@ pub struct MessageMessageChangedPreviousMessage {
     pub client_msg_id: Option<Uuid>,
     #[serde(rename = "type")]
     ty: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedPreviousMessage>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedPreviousMessageEdited {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedPreviousMessageEdited>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageChangedPreviousMessageReply {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageChangedPreviousMessageReply>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageDeleted {
     pub channel: Option<String>,
     pub deleted_ts: Option<String>,
@@ -529,10 +635,13 @@ pub struct MessageMessageDeleted {
     pub hidden: Option<bool>,
     pub previous_message: Option<Box<Message>>,
     pub ts: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageDeleted>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageMessageReplied {
     pub channel: Option<ConversationId>,
     pub event_ts: Option<Timestamp>,
@@ -540,10 +649,13 @@ pub struct MessageMessageReplied {
     pub message: Option<Box<Message>>,
     pub thread_ts: Option<Timestamp>,
     pub ts: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageMessageReplied>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessagePinnedItem {
     pub channel: Option<ConversationId>,
     pub item: Option<MessagePinnedItemItem>,
@@ -552,36 +664,50 @@ pub struct MessagePinnedItem {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessagePinnedItem>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct MessagePinnedItemItem {}
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct MessagePinnedItemItem {
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessagePinnedItemItem>,
+}
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageReminderAdd {
     pub message: Option<String>,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
     pub channel: Option<ConversationId>,
     pub text: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageReminderAdd>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageReplyBroadcast {
     pub attachments: Option<Vec<Attachment>>,
     pub channel: Option<ConversationId>,
     pub event_ts: Option<Timestamp>,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageReplyBroadcast>,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageStandard {
     pub attachments: Option<Vec<Attachment>>,
+    pub blocks: Option<Vec<Block>>,
     pub bot_id: Option<BotId>,
     pub channel: Option<ConversationId>,
     pub edited: Option<MessageStandardEdited>,
@@ -619,20 +745,27 @@ pub struct MessageStandard {
     // gracefully
     #[serde(rename = "type")]
     ty: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageStandard>,
 }
 
 // TODO: need to add the fields necessary here
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageStandardReply {
     pub ts: Timestamp,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageStandardReply>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Attachment {
     // TODO: This feels like an untagged enum...
+    pub blocks: Option<Vec<Block>>,
     pub author_icon: Option<String>,
     pub author_link: Option<String>,
     pub author_name: Option<String>,
@@ -683,10 +816,13 @@ pub struct Attachment {
     pub bot_id: Option<BotId>,
     pub is_app_unfurl: Option<bool>,
     pub msg_subtype: Option<String>, // TODO: no idea what to do with this
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Attachment>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "type")]
 pub enum Action {
@@ -709,38 +845,50 @@ pub enum Action {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Confirmation {
     dismiss_text: String,
     ok_text: String,
     text: String,
     title: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Confirmation>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ActionOption {
     text: String,
     value: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ActionOption>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageStandardAttachmentField {
     pub short: Option<bool>,
     pub title: Option<String>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageStandardAttachmentField>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageStandardEdited {
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageStandardEdited>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageUnpinnedItem {
     pub channel: Option<ConversationId>,
     pub item: Option<MessageUnpinnedItemItem>,
@@ -748,14 +896,21 @@ pub struct MessageUnpinnedItem {
     pub text: String,
     pub ts: Option<Timestamp>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageUnpinnedItem>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct MessageUnpinnedItemItem {}
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct MessageUnpinnedItemItem {
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageUnpinnedItemItem>,
+}
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageSlackbotResponse {
     #[serde(default)]
     pub text: String,
@@ -766,10 +921,13 @@ pub struct MessageSlackbotResponse {
     pub reactions: Vec<Reaction>,
     pub attachments: Option<Vec<Attachment>>,
     pub source_team: Option<TeamId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageSlackbotResponse>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct MessageThreadBroadcast {
     pub attachments: Option<Vec<Attachment>>,
     pub root: Option<MessageStandard>,
@@ -785,4 +943,7 @@ pub struct MessageThreadBroadcast {
     pub unfurl_media: Option<bool>,
     pub reactions: Option<Vec<Reaction>>,
     pub edited: Option<MessageStandardEdited>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<MessageThreadBroadcast>,
 }
@@ -62,7 +62,7 @@ impl<'de> Deserialize<'de> for ChannelName {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Bot {
     pub app_id: Option<AppId>,
     pub deleted: Option<bool>,
@@ -70,21 +70,27 @@ pub struct Bot {
     pub id: BotId,
     pub name: String,
     pub updated: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Bot>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct BotIcons {
     pub image_36: Option<String>,
     pub image_48: Option<String>,
     pub image_72: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<BotIcons>,
 }
 
 // TODO: Actually implement a type
 pub type Conversation = Channel;
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Channel {
     pub accepted_user: Option<UserId>,
     pub created: Option<Timestamp>,
@@ -115,26 +121,35 @@ pub struct Channel {
     pub unread_count: Option<u32>,
     pub unread_count_display: Option<u32>,
     pub is_starred: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Channel>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ChannelPurpose {
     pub creator: Option<String>,
     pub last_set: Option<Timestamp>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ChannelPurpose>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ChannelTopic {
     pub creator: Option<String>,
     pub last_set: Option<Timestamp>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ChannelTopic>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct File {
     pub channels: Option<Vec<String>>,
     pub comments_count: Option<u32>,
@@ -209,10 +224,13 @@ pub struct File {
     pub last_editor: Option<UserId>,
     pub state: Option<String>, // TODO Probably an enum
     pub updated: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<File>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct FileComment {
     pub comment: Option<String>,
     pub id: Option<String>,
@@ -222,10 +240,13 @@ pub struct FileComment {
     pub user: Option<UserId>,
     pub created: Option<Timestamp>,
     pub is_intro: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<FileComment>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Group {
     pub created: Option<Timestamp>,
     pub creator: Option<String>,
@@ -245,26 +266,35 @@ pub struct Group {
     pub unread_count_display: Option<u32>,
     pub last_set: Option<Timestamp>,
     pub priority: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Group>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GroupPurpose {
     pub creator: Option<String>,
     pub last_set: Option<Timestamp>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GroupPurpose>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct GroupTopic {
     pub creator: Option<String>,
     pub last_set: Option<Timestamp>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<GroupTopic>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Im {
     pub created: Option<Timestamp>,
     pub id: DmId,
@@ -273,14 +303,21 @@ pub struct Im {
     pub is_org_shared: Option<bool>,
     pub priority: Option<f64>,
     pub user: UserId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Im>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
-pub struct Command {}
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct Command {
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Command>,
+}
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 #[serde(tag = "type")]
 pub enum Subscription {
     Thread {
@@ -300,35 +337,44 @@ pub enum ChannelType {
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ShortChannelDescription {
     pub id: ::ConversationId,
     pub is_channel: bool,
     pub name: String,
     pub name_normalized: String,
     pub created: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ShortChannelDescription>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct PinnedInfo {
     pub channel: ConversationId,
     pub pinned_by: UserId,
     pub pinned_ts: Timestamp,
     pub ts: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<PinnedInfo>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct App {
     pub id: AppId,
     pub name: String,
     pub icons: Option<AppIcons>,
     pub deleted: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<App>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct AppIcons {
     pub image_32: Option<String>,
     pub image_36: Option<String>,
@@ -340,24 +386,33 @@ pub struct AppIcons {
     pub image_192: Option<String>,
     pub image_512: Option<String>,
     pub image_1024: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<AppIcons>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct DndStatus {
     pub dnd_enabled: bool,
     pub next_dnd_start_ts: Timestamp,
     pub next_dnd_end_ts: Timestamp,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<DndStatus>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct JustAFileId {
     pub id: FileId,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<JustAFileId>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Mpim {
     pub created: Option<Timestamp>,
     pub creator: Option<String>,
@@ -370,30 +425,39 @@ pub struct Mpim {
     pub name: Option<String>,
     pub unread_count: Option<u32>,
     pub unread_count_display: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Mpim>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Cursor(String); // TODO: Type safety goes here
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Paging {
     pub count: Option<u32>,
     pub page: Option<u32>,
     pub pages: Option<u32>,
     pub total: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Paging>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Reaction {
     pub count: Option<u32>,
     pub name: String,
     pub users: Option<Vec<UserId>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Reaction>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Reminder {
     pub complete_ts: Option<f32>,
     pub creator: Option<String>,
@@ -403,20 +467,26 @@ pub struct Reminder {
     pub text: String,
     pub time: Option<f32>,
     pub user: Option<UserId>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Reminder>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Team {
     pub domain: Option<String>,
     pub email_domain: Option<String>,
     pub icon: Option<TeamIcon>,
     pub id: Option<String>,
     pub name: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Team>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct TeamIcon {
     pub image_102: String,
     pub image_132: String,
@@ -426,17 +496,23 @@ pub struct TeamIcon {
     pub image_68: String,
     pub image_88: String,
     pub image_original: String,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<TeamIcon>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct ThreadInfo {
     pub complete: Option<bool>,
     pub count: Option<u32>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<ThreadInfo>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct User {
     pub color: Option<String>,
     pub deleted: Option<bool>,
@@ -459,10 +535,13 @@ pub struct User {
     pub tz_label: Option<String>,
     pub tz_offset: Option<f32>,
     pub updated: Option<Timestamp>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<User>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct Usergroup {
     pub auto_type: Option<String>,
     pub created_by: Option<String>,
@@ -480,17 +559,23 @@ pub struct Usergroup {
     pub team_id: Option<TeamId>,
     pub updated_by: Option<UserId>,
     pub user_count: Option<String>, // TODO: What on Earth
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Usergroup>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UsergroupPrefs {
     pub channels: Option<Vec<String>>,
     pub groups: Option<Vec<String>>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UsergroupPrefs>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UserProfile {
     pub name: Option<String>,
     pub avatar_hash: Option<String>,
@@ -524,14 +609,20 @@ pub struct UserProfile {
     pub status_text_canonical: Option<String>,
     pub is_restricted: Option<bool>,
     pub is_ultra_restricted: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UserProfile>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
 pub struct UserProfileFields {
     pub alt: Option<String>,
     pub label: Option<String>,
     pub value: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<UserProfileFields>,
 }
 
 fn optional_struct_or_empty_array<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
@@ -620,6 +711,8 @@ mod tests {
     }
 }
 
+mod blocks;
+pub use self::blocks::*;
 mod event;
 pub use self::event::*;
 mod message;
@@ -0,0 +1,164 @@
+//! Socket Mode, which delivers Events API payloads over a WebSocket instead of HTTP requests
+//!
+//! See https://api.slack.com/apis/connections/socket
+
+use id::*;
+use rtm::Event;
+use serde_json::Value;
+
+/// Everything Slack sends over a Socket Mode connection is wrapped in one of these. Any envelope
+/// with an `envelope_id` has to be acknowledged with an [`Ack`](struct.Ack.html), or Slack will
+/// send it again.
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub ty: EnvelopeType,
+    pub envelope_id: Option<String>,
+    /// Left as JSON so that an envelope can always be acknowledged, even when its payload is
+    /// something we can't parse
+    pub payload: Option<Value>,
+    pub accepts_response_payload: Option<bool>,
+    pub retry_attempt: Option<u32>,
+    pub retry_reason: Option<String>,
+    /// Why a `disconnect` is coming
+    pub reason: Option<String>,
+    pub num_connections: Option<u32>,
+    pub debug_info: Option<Value>,
+    pub connection_info: Option<Value>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Envelope>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvelopeType {
+    Hello,
+    /// The connection is about to be closed, and a new one should be opened
+    Disconnect,
+    EventsApi,
+    SlashCommands,
+    Interactive,
+    #[serde(other)]
+    Unknown,
+}
+
+/// The payload of an `events_api` envelope
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct EventCallback {
+    #[serde(rename = "type")]
+    ty: String,
+    pub token: Option<String>,
+    pub team_id: TeamId,
+    pub api_app_id: String,
+    pub event: Event,
+    pub event_id: String,
+    pub event_time: u64,
+    pub event_context: Option<String>,
+    pub authorizations: Option<Vec<Authorization>>,
+    pub authed_users: Option<Vec<UserId>>,
+    pub is_ext_shared_channel: Option<bool>,
+    pub context_team_id: Option<TeamId>,
+    pub context_enterprise_id: Option<String>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<EventCallback>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[cfg_attr(not(feature = "lenient"), serde(deny_unknown_fields))]
+pub struct Authorization {
+    pub enterprise_id: Option<String>,
+    pub team_id: Option<TeamId>,
+    pub user_id: UserId,
+    pub is_bot: bool,
+    pub is_enterprise_install: Option<bool>,
+    #[cfg(feature = "lenient")]
+    #[serde(flatten)]
+    pub extra: ::Extra<Authorization>,
+}
+
+/// Tells Slack an envelope arrived
+#[derive(Clone, Debug, Serialize, new)]
+pub struct Ack<'a> {
+    pub envelope_id: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rtm::Message;
+    use serde_json;
+
+    #[test]
+    fn hello_and_disconnect() {
+        let hello: Envelope = serde_json::from_str(
+            r#"{"type": "hello", "num_connections": 1,
+                "debug_info": {"host": "applink-1", "approximate_connection_time": 18060},
+                "connection_info": {"app_id": "A01"}}"#,
+        ).unwrap();
+        assert_eq!(hello.ty, EnvelopeType::Hello);
+        assert!(hello.envelope_id.is_none());
+
+        let disconnect: Envelope = serde_json::from_str(
+            r#"{"type": "disconnect", "reason": "refresh_requested",
+                "debug_info": {"host": "applink-1"}}"#,
+        ).unwrap();
+        assert_eq!(disconnect.ty, EnvelopeType::Disconnect);
+        assert_eq!(disconnect.reason.unwrap(), "refresh_requested");
+    }
+
+    #[test]
+    fn message_event() {
+        let envelope: Envelope = serde_json::from_str(
+            r#"{
+                "envelope_id": "57d6a792-4d35-4d0b-b6aa-3361493e1caf",
+                "payload": {
+                    "token": "XXYYZZ",
+                    "team_id": "T0G9PQBBK",
+                    "api_app_id": "A0G9PQBBK",
+                    "event": {
+                        "type": "message",
+                        "channel": "C0G9QF9GW",
+                        "user": "U0G9QF9C6",
+                        "text": "hello",
+                        "ts": "1355517523.000005",
+                        "event_ts": "1355517523.000005",
+                        "channel_type": "channel"
+                    },
+                    "type": "event_callback",
+                    "event_id": "Ev0G9PQBBK",
+                    "event_time": 1355517523,
+                    "authorizations": [{"enterprise_id": null, "team_id": "T0G9PQBBK",
+                        "user_id": "U0G9QF9C6", "is_bot": true, "is_enterprise_install": false}],
+                    "is_ext_shared_channel": false,
+                    "event_context": "4-eyJldCI6Im1lc3NhZ2UifQ"
+                },
+                "type": "events_api",
+                "accepts_response_payload": false,
+                "retry_attempt": 0,
+                "retry_reason": ""
+            }"#,
+        ).unwrap();
+        assert_eq!(envelope.ty, EnvelopeType::EventsApi);
+        let callback: EventCallback = serde_json::from_value(envelope.payload.unwrap()).unwrap();
+        match callback.event {
+            Event::Message {
+                message: Message::Standard(ref message),
+                ..
+            } => assert_eq!(message.text, "hello"),
+            ref other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn ack_serialize() {
+        let ack = Ack::new("57d6a792");
+        assert_eq!(
+            serde_json::to_string(&ack).unwrap(),
+            r#"{"envelope_id":"57d6a792"}"#
+        );
+    }
+}
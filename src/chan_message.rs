@@ -1,5 +1,8 @@
 use chrono::Timelike;
-use conn::{DateTime, IString};
+use conn::{Attachment, Body, DateTime, File, IString};
+use markup::{Dialect, Line, LineKind, Span, Style};
+use std::borrow::Cow;
+use unicode_segmentation::UnicodeSegmentation;
 
 lazy_static! {
     static ref COLORS: Vec<::termion::color::AnsiValue> = {
@@ -28,32 +31,56 @@ fn djb2(input: &str) -> u64 {
 
 pub struct ChanMessage {
     formatted_width: Option<usize>,
-    raw: String,
+    body: Body,
     formatted: String,
+    dialect: Dialect,
     sender: IString,
     timestamp: DateTime,
     reactions: Vec<(IString, usize)>,
+    is_mention: bool,
+    /// Said by the client itself, so the backend has never heard of it
+    local: bool,
 }
 
 impl From<::conn::Message> for ChanMessage {
     fn from(message: ::conn::Message) -> ChanMessage {
         ChanMessage {
             formatted_width: None,
-            raw: message.contents,
+            body: message.contents,
             formatted: String::new(),
+            dialect: message.dialect,
             sender: message.sender,
             timestamp: message.timestamp,
             reactions: message.reactions,
+            is_mention: message.is_mention,
+            local: false,
         }
     }
 }
 
 impl ChanMessage {
+    /// A notice from the client that's shown in a channel but never sent anywhere
+    pub fn local(message: ::conn::Message) -> ChanMessage {
+        ChanMessage {
+            local: true,
+            ..ChanMessage::from(message)
+        }
+    }
+
     // Prevent mutating the timestamp but make it visible
     pub fn timestamp(&self) -> &DateTime {
         &self.timestamp
     }
 
+    /// Whether this is a client notice, whose timestamp means nothing to the backend
+    pub fn is_local(&self) -> bool {
+        self.local
+    }
+
+    pub fn is_mention(&self) -> bool {
+        self.is_mention
+    }
+
     pub fn add_reaction(&mut self, reaction: &str) {
         let mut found = false;
         if let Some(r) = self.reactions.iter_mut().find(|rxn| rxn.0 == reaction) {
@@ -74,15 +101,14 @@ impl ChanMessage {
         self.reactions = self.reactions.iter().cloned().filter(|r| r.1 > 0).collect();
     }
 
-    pub fn edit_to(&mut self, contents: String) {
-        self.raw = contents;
+    pub fn edit_to(&mut self, contents: Body) {
+        self.body = contents;
         self.formatted_width = None;
     }
 
     pub fn formatted_to(&mut self, width: usize) -> &str {
         use std::fmt::Write;
         use termion::color::{AnsiValue, Fg, Reset};
-        use textwrap::{NoHyphenation, Wrapper};
 
         if Some(width) == self.formatted_width {
             return &self.formatted;
@@ -94,69 +120,67 @@ impl ChanMessage {
 
         self.formatted_width = Some(width);
         self.formatted.clear();
-        let indent_str = "    ";
-        // 2 for the `: ` after the name, 8 for the time
-        let sender_spacer = " ".repeat(self.sender.chars().count() + 2 + 8);
-        let wrapper = Wrapper::with_splitter(width, NoHyphenation)
-            .subsequent_indent(indent_str)
-            .initial_indent(indent_str)
-            .break_words(true);
-        let first_line_wrapper = Wrapper::with_splitter(width, NoHyphenation)
-            .subsequent_indent(indent_str)
-            .initial_indent(&sender_spacer)
-            .break_words(true);
-
-        for (l, line) in self.raw.lines().enumerate() {
-            // wrap_iter produces nothing on an empty line, so we have to supply the required newline
-            if line == "" {
-                self.formatted.push('\n');
-            }
 
-            if l == 0 {
-                for (l, wrapped_line) in first_line_wrapper.wrap_iter(line.trim_left()).enumerate()
-                {
-                    if l == 0 {
-                        let _ = write!(
-                            self.formatted,
-                            "{}({:02}:{:02}) ",
-                            Fg(AnsiValue::grayscale(8)),
-                            localtime.time().hour(),
-                            localtime.time().minute(),
-                        );
-
-                        let _ = write!(
-                            self.formatted,
-                            "{}{}{}: ",
-                            Fg(COLORS[djb2(&self.sender) as usize % COLORS.len()]),
-                            self.sender,
-                            Fg(Reset),
-                        );
-
-                        self.formatted
-                            .extend(wrapped_line.chars().skip_while(|c| c.is_whitespace()));
-                    } else {
-                        self.formatted.push_str(&wrapped_line);
-                    }
-                    self.formatted.push('\n');
-                }
-            } else {
-                for wrapped_line in wrapper.wrap_iter(&line) {
-                    self.formatted.push_str(&wrapped_line);
-                    self.formatted.push('\n');
+        let _ = write!(
+            self.formatted,
+            "{}({:02}:{:02}) ",
+            Fg(AnsiValue::grayscale(8)),
+            localtime.time().hour(),
+            localtime.time().minute(),
+        );
+        let _ = write!(
+            self.formatted,
+            "{}{}{}: ",
+            Fg(COLORS[djb2(&self.sender) as usize % COLORS.len()]),
+            self.sender,
+            Fg(Reset),
+        );
+
+        {
+            let mut rows = Rows {
+                out: &mut self.formatted,
+                width,
+                // 2 for the `: ` after the name, 8 for the time
+                column: ::layout::width(&self.sender) + 2 + 8,
+                row_empty: true,
+                first_line: true,
+                style: Style::default(),
+                prefix: String::new(),
+                prefix_width: 0,
+            };
+
+            rows.push_lines(&parse(&self.body.text, self.dialect), "", 0);
+            for attachment in &self.body.attachments {
+                if let Some(ref pretext) = attachment.pretext {
+                    rows.push_lines(&parse(pretext, self.dialect), "", 0);
                 }
+                let bar = format!("{}▌{} ", Fg(attachment_color(attachment)), Fg(Reset));
+                rows.push_lines(&attachment_lines(attachment, self.dialect), &bar, 2);
             }
+            for file in &self.body.files {
+                rows.push_lines(&file_lines(file), "", 0);
+            }
+            rows.set_style(Style::default());
+            rows.out.push('\n');
         }
 
         if !self.reactions.is_empty() {
             let _ = write!(
                 self.formatted,
                 "{}{}",
-                indent_str,
+                INDENT,
                 Fg(AnsiValue::grayscale(12))
             );
 
             for (r, count) in &self.reactions {
-                let _ = write!(self.formatted, "{}({}) ", r, count);
+                match ::emoji::lookup(r) {
+                    Some(emoji) => {
+                        let _ = write!(self.formatted, "{}({}) ", emoji, count);
+                    }
+                    None => {
+                        let _ = write!(self.formatted, "{}({}) ", r, count);
+                    }
+                }
             }
 
             let _ = write!(self.formatted, "{}", Fg(Reset));
@@ -170,3 +194,464 @@ impl ChanMessage {
         &self.formatted
     }
 }
+
+const INDENT: &str = "    ";
+
+/// Markup with any emoji shortcodes swapped for the emoji themselves
+fn parse(text: &str, dialect: Dialect) -> Vec<Line> {
+    let mut lines = ::markup::parse(text, dialect);
+    // Shortcodes inside code are left alone, like the services themselves do
+    for span in lines
+        .iter_mut()
+        .flat_map(|line| line.spans.iter_mut())
+        .filter(|span| !span.style.code)
+    {
+        if let Cow::Owned(replaced) = ::emoji::replace_shortcodes(&span.text) {
+            span.text = replaced;
+        }
+    }
+    lines
+}
+
+fn styled_line(text: &str, style: Style) -> Line {
+    Line {
+        kind: LineKind::Text,
+        spans: vec![Span {
+            text: text.to_string(),
+            style,
+        }],
+    }
+}
+
+fn attachment_color(attachment: &Attachment) -> ::termion::color::AnsiValue {
+    use termion::color::AnsiValue;
+    let color = match attachment.color {
+        Some(ref color) => color.trim_start_matches('#'),
+        None => return AnsiValue::grayscale(8),
+    };
+    match color {
+        "good" => AnsiValue::rgb(0, 4, 1),
+        "warning" => AnsiValue::rgb(5, 3, 0),
+        "danger" => AnsiValue::rgb(4, 0, 0),
+        hex if hex.len() == 6 => match u32::from_str_radix(hex, 16) {
+            // Scale each 0-255 channel down to the 0-5 of the 256-color cube
+            Ok(rgb) => AnsiValue::rgb(
+                ((rgb >> 16 & 0xff) * 6 / 256) as u8,
+                ((rgb >> 8 & 0xff) * 6 / 256) as u8,
+                ((rgb & 0xff) * 6 / 256) as u8,
+            ),
+            Err(_) => AnsiValue::grayscale(8),
+        },
+        _ => AnsiValue::grayscale(8),
+    }
+}
+
+fn attachment_lines(attachment: &Attachment, dialect: Dialect) -> Vec<Line> {
+    let bold = Style {
+        bold: true,
+        ..Style::default()
+    };
+    let mut lines = Vec::new();
+    if let Some(ref author) = attachment.author {
+        lines.push(styled_line(author, Style::default()));
+    }
+    if let Some(ref title) = attachment.title {
+        lines.push(styled_line(title, bold));
+    }
+    lines.extend(parse(&attachment.text, dialect));
+    for field in &attachment.fields {
+        lines.push(styled_line(&field.title, bold));
+        lines.extend(parse(&field.value, dialect));
+    }
+    if let Some(ref footer) = attachment.footer {
+        lines.push(styled_line(
+            footer,
+            Style {
+                italic: true,
+                ..Style::default()
+            },
+        ));
+    }
+    lines
+}
+
+fn file_lines(file: &File) -> Vec<Line> {
+    let details: Vec<String> = file
+        .kind
+        .iter()
+        .cloned()
+        .chain(file.size.map(human_size))
+        .collect();
+    let mut spans = vec![
+        Span {
+            text: String::from("File: "),
+            style: Style {
+                bold: true,
+                ..Style::default()
+            },
+        },
+        Span {
+            text: file.name.clone(),
+            style: Style::default(),
+        },
+    ];
+    if !details.is_empty() {
+        spans.push(Span {
+            text: format!(" ({})", details.join(", ")),
+            style: Style::default(),
+        });
+    }
+    let mut lines = vec![Line {
+        kind: LineKind::Text,
+        spans,
+    }];
+    if let Some(ref url) = file.url {
+        lines.push(styled_line(url, Style::default()));
+    }
+    lines
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// The wrap engine: lays styled graphemes out in rows no wider than `width` columns,
+/// starting each row after the first with `prefix` and carrying the style across the break
+struct Rows<'a> {
+    out: &'a mut String,
+    width: usize,
+    column: usize,
+    /// Whether anything has been written to this row after its prefix
+    row_empty: bool,
+    /// Whether the next line goes on the row with the sender's name
+    first_line: bool,
+    /// The style currently in effect in `out`
+    style: Style,
+    prefix: String,
+    prefix_width: usize,
+}
+
+impl<'a> Rows<'a> {
+    /// Lay out parsed lines, each starting a new row. `extra` is drawn after the indent
+    /// on every row, and takes up `extra_width` columns.
+    fn push_lines(&mut self, lines: &[Line], extra: &str, extra_width: usize) {
+        use std::fmt::Write;
+        use termion::color::{AnsiValue, Fg, Reset};
+
+        for line in lines {
+            let mut line_extra = String::from(extra);
+            let mut line_extra_width = extra_width;
+            if line.kind == LineKind::Quote {
+                let _ = write!(line_extra, "{}▎{} ", Fg(AnsiValue::grayscale(8)), Fg(Reset));
+                line_extra_width += 2;
+            }
+
+            self.prefix.clear();
+            self.prefix.push_str(INDENT);
+            self.prefix.push_str(&line_extra);
+            self.prefix_width = INDENT.len() + line_extra_width;
+            if self.first_line {
+                self.first_line = false;
+                self.out.push_str(&line_extra);
+                self.column += line_extra_width;
+            } else {
+                self.newline();
+            }
+
+            let graphemes: Vec<(&str, Style)> = line
+                .spans
+                .iter()
+                .flat_map(|span| span.text.graphemes(true).map(move |g| (g, span.style)))
+                .collect();
+            if line.kind == LineKind::Code {
+                self.push_preformatted(&graphemes);
+            } else {
+                self.push_words(&graphemes);
+            }
+        }
+    }
+
+    fn newline(&mut self) {
+        // Styles shouldn't bleed into the prefix, set_style puts them back on the next character
+        self.set_style(Style::default());
+        self.out.push('\n');
+        self.out.push_str(&self.prefix);
+        self.column = self.prefix_width;
+        self.row_empty = true;
+    }
+
+    fn set_style(&mut self, style: Style) {
+        use std::fmt::Write;
+        use termion::color::{AnsiValue, Bg};
+        use termion::style;
+
+        if style == self.style {
+            return;
+        }
+        if self.style != Style::default() {
+            let _ = write!(self.out, "{}", style::Reset);
+        }
+        if style.bold {
+            let _ = write!(self.out, "{}", style::Bold);
+        }
+        if style.italic {
+            let _ = write!(self.out, "{}", style::Italic);
+        }
+        if style.underline {
+            let _ = write!(self.out, "{}", style::Underline);
+        }
+        if style.strike {
+            let _ = write!(self.out, "{}", style::CrossedOut);
+        }
+        if style.code {
+            let _ = write!(self.out, "{}", Bg(AnsiValue::grayscale(4)));
+        }
+        self.style = style;
+    }
+
+    fn push(&mut self, grapheme: &str, style: Style) {
+        let width = ::layout::grapheme_width(grapheme);
+        if self.column + width > self.width && self.column > self.prefix_width {
+            self.newline();
+        }
+        self.set_style(style);
+        self.out.push_str(grapheme);
+        self.column += width;
+        self.row_empty = false;
+    }
+
+    /// Reflowed text: runs of whitespace become a single space or a line break
+    fn push_words(&mut self, graphemes: &[(&str, Style)]) {
+        let is_space = |g: &str| g.chars().all(char::is_whitespace);
+        let mut space = None;
+        let mut i = 0;
+        while i < graphemes.len() {
+            if is_space(graphemes[i].0) {
+                space = space.or(Some(graphemes[i].1));
+                i += 1;
+                continue;
+            }
+            let end = graphemes[i..]
+                .iter()
+                .position(|(g, _)| is_space(g))
+                .map_or(graphemes.len(), |p| i + p);
+            let word = &graphemes[i..end];
+
+            if !self.row_empty {
+                let word_width: usize = word.iter().map(|(g, _)| ::layout::grapheme_width(g)).sum();
+                let needed = word_width + if space.is_some() { 1 } else { 0 };
+                if self.column + needed > self.width {
+                    self.newline();
+                } else if let Some(style) = space {
+                    self.push(" ", style);
+                }
+            }
+            space = None;
+            // Words longer than a whole row get broken wherever the row ends
+            for &(g, style) in word {
+                self.push(g, style);
+            }
+            i = end;
+        }
+    }
+
+    /// Code keeps its whitespace, and only breaks where it has to
+    fn push_preformatted(&mut self, graphemes: &[(&str, Style)]) {
+        for &(g, style) in graphemes {
+            if g == "\t" {
+                for _ in 0..4 {
+                    self.push(" ", style);
+                }
+            } else {
+                self.push(g, style);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termion::color::{AnsiValue, Bg, Fg};
+    use termion::style;
+
+    fn render(text: &str, dialect: Dialect, width: usize) -> String {
+        let mut out = String::new();
+        {
+            let mut rows = Rows {
+                out: &mut out,
+                width,
+                column: 0,
+                row_empty: true,
+                first_line: true,
+                style: Style::default(),
+                prefix: String::new(),
+                prefix_width: 0,
+            };
+            rows.push_lines(&::markup::parse(text, dialect), "", 0);
+            rows.set_style(Style::default());
+        }
+        out
+    }
+
+    /// What the terminal shows, without the escape codes
+    fn plain(formatted: &str) -> String {
+        let mut out = String::new();
+        let mut chars = formatted.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                chars.by_ref().find(|c| c.is_ascii_alphabetic());
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn style_carries_across_wrap() {
+        let formatted = render("*one two three*", Dialect::Slack, 12);
+        assert_eq!(plain(&formatted), "one two\n    three");
+        let rows: Vec<&str> = formatted.lines().collect();
+        assert_eq!(rows[0], format!("{}one two{}", style::Bold, style::Reset));
+        assert_eq!(rows[1], format!("{}{}three{}", INDENT, style::Bold, style::Reset));
+    }
+
+    #[test]
+    fn code_keeps_whitespace() {
+        let formatted = render("```\nlet  x = 1;\n\tdone\n```", Dialect::Slack, 10);
+        // Tabs become four spaces, and long lines break mid-word rather than at the spaces
+        assert_eq!(plain(&formatted), "let  x = 1\n    ;\n        do\n    ne");
+        let code = Bg(AnsiValue::grayscale(4));
+        let row = format!("{}{};{}", INDENT, code, style::Reset);
+        assert_eq!(formatted.lines().nth(1), Some(row.as_str()));
+    }
+
+    #[test]
+    fn quote_gutter_on_every_row() {
+        let formatted = render("> quoted words here", Dialect::Discord, 12);
+        assert_eq!(plain(&formatted), "▎ quoted\n    ▎ words\n    ▎ here");
+    }
+
+    /// Everything after the sender's name, which comes after a timestamp in local time
+    fn render_body(body: Body, width: usize) -> (String, String) {
+        let mut message = ChanMessage::from(::conn::Message {
+            server: "server".into(),
+            channel: "channel".into(),
+            sender: "bot".into(),
+            contents: body,
+            is_mention: false,
+            timestamp: DateTime::now(),
+            reactions: Vec::new(),
+            dialect: Dialect::Slack,
+        });
+        let formatted = message.formatted_to(width).to_string();
+        let shown = plain(&formatted)["(00:00) bot: ".len()..].to_string();
+        (formatted, shown)
+    }
+
+    fn field(title: &str, value: &str) -> ::conn::AttachmentField {
+        ::conn::AttachmentField {
+            title: title.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn attachment_card() {
+        let attachment = Attachment {
+            color: Some("good".to_string()),
+            title: Some("Build".to_string()),
+            fields: vec![field("Branch", "main"), field("Result", "all tests passed")],
+            footer: Some("CI".to_string()),
+            ..Attachment::default()
+        };
+        let (formatted, shown) = render_body(
+            Body {
+                text: "report".to_string(),
+                attachments: vec![attachment],
+                ..Body::default()
+            },
+            20,
+        );
+        assert_eq!(
+            shown,
+            "report\n    ▌ Build\n    ▌ Branch\n    ▌ main\n    ▌ Result\n    ▌ all tests\n    \
+             ▌ passed\n    ▌ CI"
+        );
+        let bar = format!("{}▌", Fg(AnsiValue::rgb(0, 4, 1)));
+        assert_eq!(formatted.matches(&bar).count(), 7);
+    }
+
+    #[test]
+    fn fallback_only_attachment() {
+        // All Slack gives some integrations is the fallback, which becomes the text
+        let attachment = Attachment {
+            text: "Deployed v2".to_string(),
+            ..Attachment::default()
+        };
+        let (formatted, shown) = render_body(
+            Body {
+                attachments: vec![attachment],
+                ..Body::default()
+            },
+            40,
+        );
+        assert_eq!(shown, "▌ Deployed v2");
+        assert!(formatted.contains(&format!("{}▌", Fg(AnsiValue::grayscale(8)))));
+    }
+
+    #[test]
+    fn attachment_colors() {
+        let color = |c: &str| {
+            attachment_color(&Attachment {
+                color: Some(c.to_string()),
+                ..Attachment::default()
+            })
+            .0
+        };
+        assert_eq!(color("danger"), AnsiValue::rgb(4, 0, 0).0);
+        assert_eq!(color("#36a64f"), AnsiValue::rgb(1, 3, 1).0);
+        assert_eq!(color("not a color"), AnsiValue::grayscale(8).0);
+    }
+
+    #[test]
+    fn file_details() {
+        let file = File {
+            name: "notes.txt".to_string(),
+            kind: Some("Plain Text".to_string()),
+            size: Some(2048),
+            url: Some("https://example.com/notes.txt".to_string()),
+        };
+        let (_, shown) = render_body(
+            Body {
+                files: vec![file],
+                ..Body::default()
+            },
+            80,
+        );
+        assert_eq!(
+            shown,
+            "File: notes.txt (Plain Text, 2.0 KB)\n    https://example.com/notes.txt"
+        );
+    }
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(0), "0 B");
+        assert_eq!(human_size(1023), "1023 B");
+        assert_eq!(human_size(1024), "1.0 KB");
+        assert_eq!(human_size(5 * 1024 * 1024), "5.0 MB");
+        assert_eq!(human_size(3 * 1024 * 1024 * 1024 / 2), "1.5 GB");
+    }
+}
@@ -47,15 +47,69 @@ impl DateTime {
     }
 }
 
+/// Everything a message says, as opposed to who said it and where
+#[derive(Debug, Clone, Default)]
+pub struct Body {
+    pub text: String,
+    pub attachments: Vec<Attachment>,
+    pub files: Vec<File>,
+}
+
+impl From<String> for Body {
+    fn from(text: String) -> Body {
+        Body {
+            text,
+            ..Body::default()
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Body {
+    fn from(text: &'a str) -> Body {
+        Body::from(text.to_string())
+    }
+}
+
+/// A card under a message, like a link preview or a bot's report
+#[derive(Debug, Clone, Default)]
+pub struct Attachment {
+    /// Either a hex color like `#36a64f` or Slack's `good`, `warning` and `danger`
+    pub color: Option<String>,
+    /// Shown above the card rather than in it
+    pub pretext: Option<String>,
+    pub author: Option<String>,
+    pub title: Option<String>,
+    pub text: String,
+    pub fields: Vec<AttachmentField>,
+    pub footer: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AttachmentField {
+    pub title: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct File {
+    pub name: String,
+    /// Human-readable, like `PNG` or `Plain Text`
+    pub kind: Option<String>,
+    /// In bytes
+    pub size: Option<u64>,
+    pub url: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Message {
     pub server: IString,
     pub channel: IString,
     pub sender: IString,
-    pub contents: String,
+    pub contents: Body,
     pub is_mention: bool,
     pub timestamp: DateTime,
     pub reactions: Vec<(IString, usize)>,
+    pub dialect: ::markup::Dialect,
 }
 
 /// Events that a connection can send to a frontend
@@ -64,7 +118,7 @@ pub enum Event {
     MessageEdited {
         server: IString,
         channel: IString,
-        contents: String,
+        contents: Body,
         timestamp: DateTime,
     },
     HistoryLoaded {
@@ -72,14 +126,32 @@ pub enum Event {
         channel: IString,
         read_at: DateTime,
     },
+    /// Where the backend says we stopped reading a channel, which may come before its history
+    ReadState {
+        server: IString,
+        channel: IString,
+        read_at: DateTime,
+        /// How many messages are past `read_at`, if the backend said
+        unreads: Option<usize>,
+    },
     Input(termion::event::Event),
     Error(String),
     Connected(Box<Conn>),
+    /// A connection that has already been made dropped or came back
+    ConnectionChanged {
+        server: IString,
+        state: ConnectionState,
+    },
     MarkChannelRead {
         server: IString,
         channel: IString,
         read_at: DateTime,
     },
+    /// A channel that showed up after connecting, such as one that was just created
+    ChannelJoined {
+        server: IString,
+        channel: IString,
+    },
     ReactionAdded {
         server: IString,
         channel: IString,
@@ -95,17 +167,62 @@ pub enum Event {
     Resize,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connected,
+    /// Dropped, but trying again
+    Reconnecting,
+    /// Dropped for good
+    Disconnected,
+}
+
+/// What sort of conversation a channel is, for grouping them in the sidebar
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChannelKind {
+    Public,
+    Private,
+    /// With one person or a few
+    Direct,
+    /// With a bot or an app
+    App,
+}
+
+impl ChannelKind {
+    /// A guess for backends that can't say, from DMs being named after who they're with
+    pub fn from_name(name: &str) -> ChannelKind {
+        if name.starts_with('@') {
+            ChannelKind::Direct
+        } else {
+            ChannelKind::Public
+        }
+    }
+}
+
 pub trait Conn: Send {
     fn name(&self) -> &str;
 
     fn channels(&self) -> &[IString];
 
+    fn channel_kind(&self, channel: &str) -> ChannelKind {
+        ChannelKind::from_name(channel)
+    }
+
     fn send_channel_message(&mut self, _channel: &str, _contents: &str) {}
 
-    fn mark_read(&self, _channel: &str) {}
+    /// Tell the backend that everything up to and including `timestamp` has been read
+    fn mark_read(&self, _channel: &str, _timestamp: DateTime) {}
+
+    /// Fetch a channel's history ahead of everything else still waiting to load, unless it's
+    /// already been fetched
+    fn load_history(&self, _channel: &str) {}
 
     fn handle_cmd(&mut self, _cmd: &str) {}
 
+    /// How the connection is doing, for `/stats`
+    fn stats(&self) -> Option<String> {
+        None
+    }
+
     fn autocomplete(&self, _word: &str) -> Vec<String> {
         Vec::new()
     }
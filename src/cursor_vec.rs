@@ -37,6 +37,12 @@ impl<T> CursorVec<T> {
         }
     }
 
+    /// Move the cursor to `index`, which must be in bounds
+    pub fn set(&mut self, index: usize) {
+        assert!(index < self.vec.len(), "CursorVec index out of bounds");
+        self.index = index;
+    }
+
     pub fn get_first_mut(&mut self) -> &mut T {
         unsafe { self.vec.get_unchecked_mut(0) }
     }
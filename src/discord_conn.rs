@@ -1,74 +1,84 @@
 use conn::{Conn, Event, IString};
+use futures::future::{self, Either};
+use futures::Future;
 use std::sync::mpsc::SyncSender;
 
-lazy_static! {
-    pub static ref CLIENT: ::reqwest::Client = ::reqwest::Client::new();
-}
-
 pub struct DiscordConn {
     name: IString,
     channels: Vec<IString>,
 }
 
-macro_rules! deserialize_or_log {
-    ($response:expr, $type:ty) => {{
-        let text = $response.text().unwrap();
-        if $response.status().is_success() {
-            ::serde_json::from_str::<$type>(&text).map_err(|e| {
-                let pretty = ::serde_json::from_str::<::serde_json::Value>(&text)
-                    .and_then(|v| ::serde_json::to_string_pretty(&v))
-                    .unwrap_or_else(|_| String::from("Cannot pretty-print response"));
-                error!("{:#?}\n{}", e, pretty)
-            })
-        } else {
-            match ::serde_json::from_str::<::discord::Error>(&text) {
-                Ok(e) => {
-                    error!("{:#?}", e);
-                    Err(())
-                }
-                Err(e) => {
-                    error!("{:#?}\n{}", e, text);
-                    Err(())
-                }
+fn deserialize_or_log<T>(success: bool, text: &str) -> Result<T, ()>
+where
+    T: ::serde::de::DeserializeOwned,
+{
+    if success {
+        ::serde_json::from_str::<T>(text).map_err(|e| {
+            let pretty = ::serde_json::from_str::<::serde_json::Value>(text)
+                .and_then(|v| ::serde_json::to_string_pretty(&v))
+                .unwrap_or_else(|_| String::from("Cannot pretty-print response"));
+            error!("{:#?}\n{}", e, pretty)
+        })
+    } else {
+        match ::serde_json::from_str::<::discord::Error>(text) {
+            Ok(e) => {
+                error!("{:#?}", e);
+                Err(())
+            }
+            Err(e) => {
+                error!("{:#?}\n{}", e, text);
+                Err(())
             }
         }
-    }};
+    }
 }
 
-impl DiscordConn {
-    pub fn create_on(token: &str, sender: SyncSender<Event>, server: &str) -> Result<(), ()> {
-        let guilds = CLIENT
-            .get(&format!("{}{}", ::discord::BASE_URL, "/users/@me/guilds"))
-            .header("Authorization", token)
-            .send()
-            .map_err(|e| error!("{:#?}", e))
-            .and_then(|mut r| deserialize_or_log!(r, Vec<::discord::Guild>))?;
-
-        let guild = guilds.iter().find(|g| g.name == server).unwrap();
-
-        let channels = CLIENT
-            .get(&format!(
-                "{}/guilds/{}/channels",
-                ::discord::BASE_URL,
-                guild.id
-            ))
-            .header("Authorization", token)
-            .send()
-            .map_err(|e| error!("{:#?}", e))
-            .and_then(|mut r| deserialize_or_log!(r, Vec<::discord::Channel>))?;
-
-        let channel_names: Vec<IString> = channels
-            .iter()
-            .filter_map(|c| c.name.as_ref())
-            .map(|name| name.as_str().into())
-            .collect();
+/// GET a path under the Discord API. This has to run on the event loop.
+fn get<T>(token: &str, path: &str) -> impl Future<Item = T, Error = ()>
+where
+    T: ::serde::de::DeserializeOwned,
+{
+    let request = ::runtime::CLIENT
+        .get(&format!("{}{}", ::discord::BASE_URL, path))
+        .header("Authorization", token)
+        .send()
+        .and_then(|mut response| {
+            let success = response.status().is_success();
+            response.text().map(move |text| (success, text))
+        });
+    ::runtime::limit(request)
+        .map_err(|e| error!("{:#?}", e))
+        .and_then(|(success, text)| deserialize_or_log(success, &text))
+}
 
-        let _ = sender.send(Event::Connected(Box::new(Self {
-            name: guild.name.as_str().into(),
-            channels: channel_names,
-        })));
+impl DiscordConn {
+    pub fn create_on(
+        token: String,
+        sender: SyncSender<Event>,
+        server: String,
+    ) -> impl Future<Item = (), Error = ()> {
+        get::<Vec<::discord::Guild>>(&token, "/users/@me/guilds").and_then(move |guilds| {
+            let guild = match guilds.into_iter().find(|g| g.name == server) {
+                Some(guild) => guild,
+                None => {
+                    error!("Not a member of any Discord server named {}", server);
+                    return Either::A(future::err(()));
+                }
+            };
+            let path = format!("/guilds/{}/channels", guild.id);
+            Either::B(get::<Vec<::discord::Channel>>(&token, &path).map(move |channels| {
+                let channel_names: Vec<IString> = channels
+                    .iter()
+                    .filter_map(|c| c.name.as_ref())
+                    .map(|name| name.as_str().into())
+                    .collect();
 
-        Ok(())
+                let _ = sender.send(Event::Connected(Box::new(DiscordConn {
+                    name: guild.name.as_str().into(),
+                    channels: channel_names,
+                })));
+            }))
+        })
     }
 }
 
@@ -0,0 +1,144 @@
+//! The standard Unicode emoji, looked up by the shortcodes chat services use for them
+use regex::{Captures, Regex};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+struct Emoji {
+    text: &'static str,
+    /// Light through dark, which Slack calls skin-tone-2 through skin-tone-6
+    skin_tones: Option<Vec<&'static str>>,
+}
+
+lazy_static! {
+    static ref EMOJI: HashMap<&'static str, Emoji> = {
+        let mut emoji = HashMap::new();
+        for line in include_str!("emoji.txt").lines() {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut columns = line.split('\t');
+            let (text, names) = match (columns.next(), columns.next()) {
+                (Some(text), Some(names)) => (text, names),
+                _ => continue,
+            };
+            let skin_tones = columns.next().map(|tones| tones.split(' ').collect::<Vec<_>>());
+            for name in names.split(' ') {
+                emoji.insert(
+                    name,
+                    Emoji {
+                        text,
+                        skin_tones: skin_tones.clone(),
+                    },
+                );
+            }
+        }
+        emoji
+    };
+    static ref SHORTCODES: Vec<&'static str> = {
+        let mut names: Vec<_> = EMOJI.keys().cloned().collect();
+        names.sort();
+        names
+    };
+    static ref SHORTCODE_REGEX: Regex =
+        Regex::new(r":(?P<name>[a-z0-9_+'-]+):(?::skin-tone-(?P<tone>[2-6]):)?").unwrap();
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Whether shortcodes should be drawn as Unicode, which needs a UTF-8 locale and a font that has them
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Guess from the locale whether the terminal will draw emoji at all
+pub fn locale_supports_unicode() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|var| ::std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map_or(false, |value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+}
+
+/// Every standard shortcode, sorted
+pub fn shortcodes() -> &'static [&'static str] {
+    &SHORTCODES
+}
+
+fn lookup_tone(name: &str, tone: Option<usize>) -> Option<&'static str> {
+    let emoji = EMOJI.get(name)?;
+    match (tone, &emoji.skin_tones) {
+        (Some(tone), Some(tones)) => tones.get(tone - 2).cloned(),
+        _ => Some(emoji.text),
+    }
+}
+
+/// The emoji for a name as it appears in a reaction, like `thumbsup` or `thumbsup::skin-tone-2`
+pub fn lookup(name: &str) -> Option<&'static str> {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return None;
+    }
+    let mut parts = name.splitn(2, "::skin-tone-");
+    let base = parts.next()?;
+    let tone = parts.next().and_then(|t| t.parse().ok()).filter(|t| (2..=6).contains(t));
+    lookup_tone(base, tone)
+}
+
+/// Replace every `:shortcode:` in some text that names a standard emoji
+pub fn replace_shortcodes(text: &str) -> Cow<str> {
+    replace(text, ENABLED.load(Ordering::Relaxed))
+}
+
+fn replace(text: &str, enabled: bool) -> Cow<str> {
+    if !enabled || !text.contains(':') {
+        return Cow::Borrowed(text);
+    }
+    SHORTCODE_REGEX.replace_all(text, |caps: &Captures| {
+        let tone = caps.name("tone").and_then(|t| t.as_str().parse().ok());
+        match lookup_tone(&caps["name"], tone) {
+            Some(emoji) => emoji.to_string(),
+            None => caps[0].to_string(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aliases() {
+        assert_eq!(lookup("thumbsup"), Some("👍"));
+        assert_eq!(lookup("+1"), Some("👍"));
+        assert_eq!(lookup("not_an_emoji"), None);
+        assert!(shortcodes().contains(&"+1"));
+    }
+
+    #[test]
+    fn skin_tones() {
+        assert_eq!(lookup("wave::skin-tone-2"), Some("👋🏻"));
+        assert_eq!(lookup("wave::skin-tone-6"), Some("👋🏿"));
+        // Out of range tones fall back to the plain emoji
+        assert_eq!(lookup("wave::skin-tone-9"), Some("👋"));
+        assert_eq!(replace(":wave::skin-tone-3: hi", true), "👋🏼 hi");
+    }
+
+    #[test]
+    fn replaces_known_shortcodes() {
+        assert_eq!(replace("ship it :+1: :thumbsup:", true), "ship it 👍 👍");
+        assert_eq!(replace("time is 10:30:00", true), "time is 10:30:00");
+        assert_eq!(replace(":nope: :wave:", true), ":nope: 👋");
+    }
+
+    #[test]
+    fn disabled() {
+        assert_eq!(replace(":wave:", false), ":wave:");
+        assert!(match replace(":wave:", false) {
+            Cow::Borrowed(_) => true,
+            Cow::Owned(_) => false,
+        });
+    }
+}
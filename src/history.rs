@@ -0,0 +1,153 @@
+//! Shell-like history of sent messages, per channel and across all of them
+
+const HISTORY_FILE: &str = "history.json";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    server: String,
+    channel: String,
+    text: String,
+}
+
+pub struct History {
+    /// Oldest first
+    entries: Vec<Entry>,
+    limit: usize,
+}
+
+impl History {
+    pub fn load(limit: usize) -> Self {
+        let mut history = History {
+            entries: ::state::load(HISTORY_FILE).unwrap_or_default(),
+            limit,
+        };
+        history.truncate();
+        history
+    }
+
+    fn truncate(&mut self) {
+        if self.entries.len() > self.limit {
+            let excess = self.entries.len() - self.limit;
+            self.entries.drain(..excess);
+        }
+    }
+
+    pub fn push(&mut self, server: &str, channel: &str, text: &str) {
+        if self.remember(server, channel, text) {
+            ::state::save(HISTORY_FILE, &self.entries);
+        }
+    }
+
+    /// Add an entry without saving, returns whether anything changed
+    fn remember(&mut self, server: &str, channel: &str, text: &str) -> bool {
+        if self.limit == 0 {
+            return false;
+        }
+        // Like HISTCONTROL=ignoredups, sending the same thing twice only remembers it once
+        let duplicate = self
+            .channel_entries(server, channel)
+            .next()
+            .map_or(false, |previous| previous == text);
+        if duplicate {
+            return false;
+        }
+        self.entries.push(Entry {
+            server: server.to_string(),
+            channel: channel.to_string(),
+            text: text.to_string(),
+        });
+        self.truncate();
+        true
+    }
+
+    /// Everything sent to one channel, newest first
+    pub fn channel_entries<'a>(
+        &'a self,
+        server: &'a str,
+        channel: &'a str,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.entries
+            .iter()
+            .rev()
+            .filter(move |e| e.server == server && e.channel == channel)
+            .map(|e| e.text.as_str())
+    }
+
+    /// The `skip`th distinct entry from any channel containing `query`, searching from the newest
+    pub fn search(&self, query: &str, skip: usize) -> Option<&str> {
+        let mut seen: Vec<&str> = Vec::new();
+        for entry in self.entries.iter().rev() {
+            if entry.text.contains(query) && !seen.contains(&entry.text.as_str()) {
+                if seen.len() == skip {
+                    return Some(&entry.text);
+                }
+                seen.push(&entry.text);
+            }
+        }
+        None
+    }
+}
+
+/// State of an in-progress Ctrl-R search
+pub struct Search {
+    pub query: String,
+    /// How many older matches have been skipped with repeated Ctrl-R
+    pub skip: usize,
+    /// The input line from before the search started, restored if it's cancelled
+    pub original: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent(limit: usize, messages: &[(&str, &str)]) -> History {
+        let mut history = History {
+            entries: Vec::new(),
+            limit,
+        };
+        for &(channel, text) in messages {
+            history.remember("server", channel, text);
+        }
+        history
+    }
+
+    #[test]
+    fn channel_entries_only_recall_that_channel() {
+        let history = sent(10, &[("a", "one"), ("b", "two"), ("a", "three")]);
+        let recalled: Vec<_> = history.channel_entries("server", "a").collect();
+        assert_eq!(recalled, vec!["three", "one"]);
+        assert_eq!(history.channel_entries("other", "a").count(), 0);
+    }
+
+    #[test]
+    fn search_covers_every_channel() {
+        let history = sent(10, &[("a", "hello there"), ("b", "hello again"), ("a", "bye")]);
+        assert_eq!(history.search("hello", 0), Some("hello again"));
+        assert_eq!(history.search("hello", 1), Some("hello there"));
+        assert_eq!(history.search("hello", 2), None);
+    }
+
+    #[test]
+    fn search_skips_repeats() {
+        let history = sent(10, &[("a", "hello"), ("b", "hello"), ("a", "help")]);
+        assert_eq!(history.search("hel", 0), Some("help"));
+        assert_eq!(history.search("hel", 1), Some("hello"));
+        assert_eq!(history.search("hel", 2), None);
+    }
+
+    #[test]
+    fn consecutive_duplicates_are_ignored() {
+        let history = sent(10, &[("a", "same"), ("a", "same"), ("b", "same")]);
+        assert_eq!(history.entries.len(), 2);
+    }
+
+    #[test]
+    fn limit_drops_oldest() {
+        let history = sent(2, &[("a", "one"), ("b", "two"), ("a", "three")]);
+        let recalled: Vec<_> = history.channel_entries("server", "a").collect();
+        assert_eq!(recalled, vec!["three"]);
+        assert_eq!(history.entries.len(), 2);
+        assert!(sent(0, &[("a", "one")]).entries.is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! Measuring text in terminal columns, one grapheme cluster at a time
+//!
+//! Counting `chars()` goes wrong as soon as a message has CJK, emoji or combining marks in it,
+//! so anything that lines text up on screen should go through here.
+use std::borrow::Cow;
+use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
+use unicode_width::UnicodeWidthChar;
+
+/// Columns taken up by a single grapheme cluster
+pub fn grapheme_width(grapheme: &str) -> usize {
+    let mut chars = grapheme.chars();
+    let first = match chars.next() {
+        Some(c) => c,
+        None => return 0,
+    };
+    // A variation selector asks for emoji presentation, which terminals draw double-width
+    if grapheme.contains('\u{fe0f}') {
+        return 2;
+    }
+    // Everything after the base character (combining marks, ZWJ sequences, skin tones)
+    // is drawn in the same cell(s) as it
+    first.width().unwrap_or(0)
+}
+
+/// Columns taken up by a whole string
+pub fn width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_width).sum()
+}
+
+/// Shorten `text` to fit in `max_width` columns, marking that it was cut off with "..."
+pub fn truncate(text: &str, max_width: usize) -> Cow<str> {
+    if width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let mut used = 0;
+    let mut end = 0;
+    for (i, g) in text.grapheme_indices(true) {
+        let w = grapheme_width(g);
+        if used + w + 3 > max_width {
+            break;
+        }
+        used += w;
+        end = i + g.len();
+    }
+    Cow::Owned(format!("{}...", &text[..end]))
+}
+
+/// Byte offset of the grapheme after the one at `pos`
+pub fn next_grapheme(text: &str, pos: usize) -> usize {
+    text[pos..]
+        .graphemes(true)
+        .next()
+        .map_or(text.len(), |g| pos + g.len())
+}
+
+/// Byte offset of the grapheme before `pos`
+pub fn prev_grapheme(text: &str, pos: usize) -> usize {
+    text[..pos]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// Lays text out in rows `width` columns wide, yielding `(byte offset, grapheme, row, column)`.
+/// A wide grapheme that would straddle the edge moves to the next row instead.
+pub struct Placed<'a> {
+    graphemes: GraphemeIndices<'a>,
+    width: usize,
+    row: usize,
+    column: usize,
+}
+
+impl<'a> Iterator for Placed<'a> {
+    type Item = (usize, &'a str, usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (i, g) = self.graphemes.next()?;
+        let w = grapheme_width(g);
+        if self.column + w > self.width && self.column > 0 {
+            self.row += 1;
+            self.column = 0;
+        }
+        let placed = (i, g, self.row, self.column);
+        self.column += w;
+        Some(placed)
+    }
+}
+
+pub fn place(text: &str, width: usize) -> Placed {
+    Placed {
+        graphemes: text.grapheme_indices(true),
+        width,
+        row: 0,
+        column: 0,
+    }
+}
+
+/// The row and column where a cursor at byte offset `pos` is drawn
+pub fn position(text: &str, pos: usize, width: usize) -> (usize, usize) {
+    let mut placed = place(text, width);
+    for (i, _, row, column) in &mut placed {
+        if i >= pos {
+            return (row, column);
+        }
+    }
+    // Past the end, so wherever the next grapheme would go
+    if placed.column >= width {
+        (placed.row + 1, 0)
+    } else {
+        (placed.row, placed.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ascii_is_one_column_per_char() {
+        assert_eq!(width("hello"), 5);
+        assert_eq!(grapheme_width("a"), 1);
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(width("日本語"), 6);
+        assert_eq!(width("a日b"), 4);
+        assert_eq!(grapheme_width("😀"), 2);
+    }
+
+    #[test]
+    fn zero_width_characters() {
+        // e followed by a combining acute accent is one column
+        assert_eq!(width("e\u{301}"), 1);
+        assert_eq!("e\u{301}".graphemes(true).count(), 1);
+        assert_eq!(width("a\u{200b}b"), 2);
+        assert_eq!(width(""), 0);
+    }
+
+    #[test]
+    fn emoji_sequences() {
+        // Family: man, ZWJ, woman, ZWJ, girl
+        assert_eq!(width("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}"), 2);
+        // Thumbs up with a skin tone modifier
+        assert_eq!(width("\u{1f44d}\u{1f3fd}"), 2);
+        // Heart with the emoji presentation selector
+        assert_eq!(width("\u{2764}\u{fe0f}"), 2);
+    }
+
+    #[test]
+    fn truncation_counts_columns() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("abcdefghij", 8), "abcde...");
+        assert_eq!(truncate("日本語日本語", 8), "日本...");
+        assert_eq!(truncate("e\u{301}e\u{301}e\u{301}e\u{301}e\u{301}", 4), "e\u{301}...");
+    }
+
+    #[test]
+    fn grapheme_boundaries() {
+        let text = "ae\u{301}日";
+        assert_eq!(next_grapheme(text, 0), 1);
+        assert_eq!(next_grapheme(text, 1), 4);
+        assert_eq!(next_grapheme(text, 4), 7);
+        assert_eq!(next_grapheme(text, 7), 7);
+        assert_eq!(prev_grapheme(text, 7), 4);
+        assert_eq!(prev_grapheme(text, 4), 1);
+        assert_eq!(prev_grapheme(text, 0), 0);
+    }
+
+    #[test]
+    fn wide_characters_wrap_whole() {
+        let rows: Vec<_> = place("ab日c", 3).map(|(_, g, r, c)| (g, r, c)).collect();
+        assert_eq!(rows, vec![("a", 0, 0), ("b", 0, 1), ("日", 1, 0), ("c", 1, 2)]);
+    }
+
+    #[test]
+    fn cursor_position() {
+        assert_eq!(position("abc", 0, 10), (0, 0));
+        assert_eq!(position("abc", 3, 10), (0, 3));
+        assert_eq!(position("日本", 3, 10), (0, 2));
+        assert_eq!(position("日本", 6, 10), (0, 4));
+        assert_eq!(position("abc", 3, 3), (1, 0));
+        assert_eq!(position("ab日", 2, 3), (1, 0));
+    }
+}
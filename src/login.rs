@@ -0,0 +1,376 @@
+//! `omnichat login slack`, which gets a token through Slack's OAuth flow instead of making people
+//! dig one out by hand
+//!
+//! Slack sends the browser back to a listener on the loopback interface with a code, which we
+//! trade for a token and add to the config file as a new `[[slack]]` account.
+use futures::Future;
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: omnichat login slack --client-id ID --client-secret SECRET
+    [--user-scope SCOPES] [--scope SCOPES] [--port PORT]
+    [--authorize-url URL] [--api-url URL]
+
+The client ID and secret come from your Slack app's Basic Information page, and can also be given
+in $OMNICHAT_SLACK_CLIENT_ID and $OMNICHAT_SLACK_CLIENT_SECRET. The app needs
+http://127.0.0.1:PORT/ as a redirect URL.";
+
+/// Everything a user token needs to read and write the way the client does
+const USER_SCOPES: &str = "channels:history,channels:read,channels:write,groups:history,\
+                           groups:read,groups:write,im:history,im:read,im:write,mpim:history,\
+                           mpim:read,mpim:write,chat:write,reactions:read,reactions:write,\
+                           users:read,emoji:read,team:read";
+
+/// How long someone has to finish logging in on Slack's page
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// How long a connection to the listener gets to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+struct Options {
+    client_id: String,
+    client_secret: String,
+    scope: Option<String>,
+    user_scope: String,
+    port: u16,
+    authorize_url: String,
+    /// Where Web API methods live, which can point at a fake server for testing
+    api_url: String,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Self, String> {
+        let mut options = Options {
+            client_id: ::std::env::var("OMNICHAT_SLACK_CLIENT_ID").unwrap_or_default(),
+            client_secret: ::std::env::var("OMNICHAT_SLACK_CLIENT_SECRET").unwrap_or_default(),
+            scope: None,
+            user_scope: String::from(USER_SCOPES),
+            port: 8395,
+            authorize_url: String::from("https://slack.com/oauth/v2/authorize"),
+            api_url: String::from("https://slack.com/api"),
+        };
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| format!("{} needs a value\n\n{}", flag, USAGE))?
+                .clone();
+            match flag.as_str() {
+                "--client-id" => options.client_id = value,
+                "--client-secret" => options.client_secret = value,
+                "--scope" => options.scope = Some(value),
+                "--user-scope" => options.user_scope = value,
+                "--port" => {
+                    options.port = value
+                        .parse()
+                        .map_err(|_| format!("{} is not a port number", value))?
+                }
+                "--authorize-url" => options.authorize_url = value,
+                "--api-url" => options.api_url = value.trim_end_matches('/').to_string(),
+                _ => return Err(format!("Unknown option {}\n\n{}", flag, USAGE)),
+            }
+        }
+        if options.client_id.is_empty() || options.client_secret.is_empty() {
+            return Err(format!("A client ID and secret are required\n\n{}", USAGE));
+        }
+        Ok(options)
+    }
+}
+
+struct Account {
+    team: String,
+    token: String,
+}
+
+/// Run `omnichat login`, with `args` being everything after `login`
+pub fn run(args: &[String], config_path: &Path) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("slack") => {}
+        _ => return Err(String::from(USAGE)),
+    }
+    let options = Options::parse(&args[1..])?;
+    let account = slack(&options, |url| {
+        println!("Log in to Slack by opening this page in a browser:\n\n{}\n", url);
+        // Not everyone has xdg-open, and the URL is printed anyway
+        let _ = ::std::process::Command::new("xdg-open")
+            .arg(url)
+            .stdout(::std::process::Stdio::null())
+            .stderr(::std::process::Stdio::null())
+            .spawn();
+    })?;
+    save_account(config_path, &account)
+        .map_err(|e| format!("Couldn't write to {:?}: {}", config_path, e))?;
+    println!("Logged in to {}, and added it to {:?}", account.team, config_path);
+    Ok(())
+}
+
+fn slack<F: FnOnce(&str)>(options: &Options, open: F) -> Result<Account, String> {
+    let listener = TcpListener::bind(("127.0.0.1", options.port))
+        .map_err(|e| format!("Couldn't listen on port {}: {}", options.port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}/", port);
+    let state = random_state()?;
+
+    let mut query = vec![
+        ("client_id", options.client_id.as_str()),
+        ("user_scope", options.user_scope.as_str()),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("state", state.as_str()),
+    ];
+    if let Some(ref scope) = options.scope {
+        query.push(("scope", scope.as_str()));
+    }
+    let query = ::serde_urlencoded::to_string(&query).map_err(|e| e.to_string())?;
+    open(&format!("{}?{}", options.authorize_url, query));
+
+    let code = wait_for_code(&listener, &state, LOGIN_TIMEOUT)?;
+
+    use slack::http::oauth::{V2AccessRequest, V2AccessResponse};
+    let mut request = V2AccessRequest::new(&options.client_id, &options.client_secret, &code);
+    request.redirect_uri = Some(&redirect_uri);
+    let response = ::runtime::CLIENT
+        .post(&format!("{}/oauth.v2.access", options.api_url))
+        .form(&request)
+        .send()
+        .and_then(|mut response| response.text());
+    let response: V2AccessResponse = ::runtime::spawn(response)
+        .wait()
+        .map_err(|e| e.to_string())
+        .and_then(|body| ::slack_conn::parse_response(body).map_err(|e| e.to_string()))?;
+
+    // A user token acts as the person logging in, which is what a chat client should do
+    let token = response
+        .authed_user
+        .access_token
+        .or(response.access_token)
+        .ok_or_else(|| String::from("Slack didn't send back a token"))?;
+    Ok(Account {
+        team: response.team.name,
+        token,
+    })
+}
+
+#[derive(Deserialize)]
+struct Redirect {
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+}
+
+/// Answer requests to the listener until the browser comes back from Slack, or give up after
+/// `timeout` if nobody finishes logging in
+fn wait_for_code(
+    listener: &TcpListener,
+    state: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let deadline = Instant::now() + timeout;
+    listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return Err(String::from("Gave up waiting for the redirect from Slack"));
+        }
+        let mut stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+                continue;
+            }
+            Err(_) => continue,
+        };
+        // A connection that never sends anything shouldn't hold up the real redirect
+        let read_timeout = ::std::cmp::min(REQUEST_TIMEOUT, deadline - now);
+        if stream.set_nonblocking(false).is_err()
+            || stream.set_read_timeout(Some(read_timeout)).is_err()
+        {
+            continue;
+        }
+        let redirect = match read_redirect(&mut stream) {
+            Some(redirect) => redirect,
+            // Probably the browser asking for a favicon
+            None => {
+                respond(&mut stream, "404 Not Found", "Nothing here");
+                continue;
+            }
+        };
+        if redirect.state.as_ref().map(String::as_str) != Some(state) {
+            respond(&mut stream, "400 Bad Request", "This login attempt has expired.");
+            continue;
+        }
+        return match (redirect.code, redirect.error) {
+            (Some(code), _) => {
+                respond(
+                    &mut stream,
+                    "200 OK",
+                    "Logged in to omnichat. You can close this window.",
+                );
+                Ok(code)
+            }
+            (None, error) => {
+                let error = error.unwrap_or_else(|| String::from("no code given"));
+                respond(&mut stream, "200 OK", &format!("Login failed: {}", error));
+                Err(format!("Slack login failed: {}", error))
+            }
+        };
+    }
+}
+
+/// Pull the query out of a request like `GET /?code=...&state=... HTTP/1.1`
+fn read_redirect(stream: &mut TcpStream) -> Option<Redirect> {
+    let mut line = String::new();
+    BufReader::new(&mut *stream).read_line(&mut line).ok()?;
+    let target = line.split(' ').nth(1)?;
+    let query = target.splitn(2, '?').nth(1)?;
+    let redirect: Redirect = ::serde_urlencoded::from_str(query).ok()?;
+    if redirect.code.is_none() && redirect.error.is_none() {
+        return None;
+    }
+    Some(redirect)
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &str) {
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+}
+
+/// Something unguessable to tie the redirect to this login, so another page can't feed us a code
+fn random_state() -> Result<String, String> {
+    let mut bytes = [0; 16];
+    ::std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut bytes))
+        .map_err(|e| format!("Couldn't read /dev/urandom: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn save_account(config_path: &Path, account: &Account) -> ::std::io::Result<()> {
+    let mut file = ::secret::private()
+        .create(true)
+        .append(true)
+        .open(config_path)?;
+    ::secret::restrict(config_path)?;
+    writeln!(
+        file,
+        "\n[[slack]]\n# {}\ntoken = {}",
+        account.team.replace('\n', " "),
+        ::toml::Value::String(account.token.clone())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Stands in for slack.com, answering one oauth.v2.access call
+    fn fake_api() -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.to_lowercase().starts_with("content-length:") {
+                    length = line[15..].trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+
+            let response = r#"{"ok": true, "app_id": "A0KRD7HC3", "scope": "",
+                "team": {"name": "Slack Softball Team", "id": "T9TK3CUKW"},
+                "enterprise": null, "is_enterprise_install": false,
+                "authed_user": {"id": "U1234", "scope": "chat:write",
+                    "access_token": "xoxp-1234", "token_type": "user"}}"#;
+            let _ = write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                 Connection: close\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            request
+        });
+        (url, server)
+    }
+
+    /// Plays the part of the browser being sent back from Slack's authorize page
+    fn redirect_back(authorize_url: &str, code: &str) {
+        let query = authorize_url.splitn(2, '?').nth(1).unwrap();
+        let params: Vec<(String, String)> = ::serde_urlencoded::from_str(query).unwrap();
+        let get = |name: &str| params.iter().find(|p| p.0 == name).unwrap().1.clone();
+        let redirect_uri = get("redirect_uri");
+        let state = get("state");
+        let address = redirect_uri["http://".len()..].trim_end_matches('/').to_string();
+        let code = code.to_string();
+        thread::spawn(move || {
+            // A stray request first, which shouldn't end the login
+            let mut favicon = TcpStream::connect(&address).unwrap();
+            write!(favicon, "GET /favicon.ico HTTP/1.1\r\n\r\n").unwrap();
+            let _ = favicon.read_to_string(&mut String::new());
+
+            let mut stream = TcpStream::connect(&address).unwrap();
+            write!(
+                stream,
+                "GET /?code={}&state={} HTTP/1.1\r\nHost: {}\r\n\r\n",
+                code, state, address
+            ).unwrap();
+            let mut response = String::new();
+            let _ = stream.read_to_string(&mut response);
+            assert!(response.starts_with("HTTP/1.1 200 OK"));
+        });
+    }
+
+    #[test]
+    fn login_against_fake_server() {
+        let (api_url, server) = fake_api();
+        let options = Options::parse(&[
+            "--client-id".to_string(),
+            "1234.5678".to_string(),
+            "--client-secret".to_string(),
+            "shh".to_string(),
+            "--port".to_string(),
+            "0".to_string(),
+            "--api-url".to_string(),
+            api_url,
+        ]).unwrap();
+
+        let account = slack(&options, |url| {
+            assert!(url.starts_with("https://slack.com/oauth/v2/authorize?client_id=1234.5678"));
+            redirect_back(url, "the-code");
+        }).unwrap();
+        assert_eq!(account.team, "Slack Softball Team");
+        assert_eq!(account.token, "xoxp-1234");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /api/oauth.v2.access HTTP/1.1"));
+        assert!(request.contains("code=the-code"));
+        assert!(request.contains("client_secret=shh"));
+    }
+
+    #[test]
+    fn gives_up_waiting_for_the_redirect() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        // Connects but never says anything
+        let _silent = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let started = Instant::now();
+        assert!(wait_for_code(&listener, "state", Duration::from_millis(300)).is_err());
+        assert!(started.elapsed() < REQUEST_TIMEOUT);
+    }
+}
@@ -20,9 +20,11 @@ extern crate serde_urlencoded;
 extern crate signal_hook;
 extern crate slack;
 extern crate termion;
-extern crate textwrap;
 extern crate tokio_core;
+extern crate tokio_timer;
 extern crate toml;
+extern crate unicode_segmentation;
+extern crate unicode_width;
 extern crate websocket;
 
 #[macro_use]
@@ -31,13 +33,34 @@ mod bimap;
 mod chan_message;
 mod cursor_vec;
 mod discord_conn;
+mod emoji;
+mod history;
+mod layout;
 mod logger;
+mod login;
+mod markup;
+mod panes;
+mod runtime;
+mod secret;
+mod sidebar;
 mod slack_conn;
+mod slack_scheduler;
+mod state;
+mod switcher;
 mod tui;
+mod vi;
 
 #[derive(Debug, Deserialize, Clone)]
 struct SlackConfig {
-    token: String,
+    /// The token itself, or a `$VARIABLE` holding it
+    token: Option<String>,
+    /// A command that prints the token, used instead of `token`
+    token_command: Option<String>,
+    #[serde(default)]
+    transport: slack_conn::Transport,
+    /// The app-level token Socket Mode connects with
+    app_token: Option<String>,
+    app_token_command: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -45,11 +68,58 @@ struct DiscordConfig {
     name: String,
 }
 
+/// Settings for the client itself, from the `[client]` table
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+struct ClientConfig {
+    vi_mode: bool,
+    /// Draw emoji shortcodes as the emoji themselves, if the locale is UTF-8
+    unicode_emoji: bool,
+    /// How many sent messages to remember, across all channels
+    history_size: usize,
+    /// Channels to read last, as `server/channel` or a channel name on any server
+    muted_channels: Vec<String>,
+    /// Channels for the starred section of the sidebar, written like `muted_channels`
+    starred_channels: Vec<String>,
+    /// The sidebar's sections in order, from starred, channels, private, dms and apps
+    sidebar_sections: Vec<sidebar::Section>,
+    /// alphabetical or activity
+    sidebar_sort: sidebar::Sort,
+    /// Leave channels with nothing in them out of the sidebar
+    hide_inactive_channels: bool,
+    sidebar_width: u16,
+    /// Wrap the server bar onto more rows when it's too long, instead of scrolling it
+    wrap_server_bar: bool,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            vi_mode: false,
+            unicode_emoji: true,
+            history_size: 1000,
+            muted_channels: Vec::new(),
+            starred_channels: Vec::new(),
+            sidebar_sections: {
+                use sidebar::Section::*;
+                vec![Starred, Channels, Private, Dms, Apps]
+            },
+            sidebar_sort: sidebar::Sort::Alphabetical,
+            hide_inactive_channels: false,
+            sidebar_width: 20,
+            wrap_server_bar: false,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct Config {
     discord_token: Option<String>,
+    discord_token_command: Option<String>,
     slack: Option<Vec<SlackConfig>>,
     discord: Option<Vec<DiscordConfig>>,
+    #[serde(default)]
+    client: ClientConfig,
 }
 
 fn main() {
@@ -67,6 +137,15 @@ fn main() {
         std::process::exit(1)
     });
     let config_path = PathBuf::from(homedir).join(".omnichat.toml");
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("login") {
+        if let Err(e) = login::run(&args[1..], &config_path) {
+            println!("{}", e);
+            std::process::exit(1)
+        }
+        return;
+    }
     let mut contents = String::new();
     File::open(&config_path)
         .unwrap_or_else(|_| {
@@ -86,33 +165,69 @@ fn main() {
         println!("{:?} is not a valid omnichat config file", &config_path);
         std::process::exit(1)
     });
+    if let Err(e) = secret::restrict(&config_path) {
+        println!("Couldn't make {:?} private: {}", config_path, e);
+    }
+
+    // Token commands may need to ask for a password, so run them before the TUI takes the terminal
+    let credential = |value: &Option<String>, command: &Option<String>, name: &str| {
+        secret::resolve(value.as_ref().map(String::as_str), command.as_ref().map(String::as_str))
+            .unwrap_or_else(|e| {
+                println!("Couldn't get the {} from {:?}: {}", name, config_path, e);
+                std::process::exit(1)
+            })
+    };
+    let slack_accounts = config
+        .slack
+        .unwrap_or_default()
+        .into_iter()
+        .map(|c| {
+            let token = credential(&c.token, &c.token_command, "Slack token").unwrap_or_else(|| {
+                println!("Every [[slack]] section needs a token or token_command");
+                std::process::exit(1)
+            });
+            let app_token = credential(&c.app_token, &c.app_token_command, "Slack app token");
+            (token, c.transport, app_token)
+        })
+        .collect::<Vec<_>>();
+    let discord_token = credential(
+        &config.discord_token,
+        &config.discord_token_command,
+        "Discord token",
+    );
 
-    let tui = tui::Tui::new();
+    emoji::set_enabled(config.client.unicode_emoji && emoji::locale_supports_unicode());
 
-    // Init the global logger
-    log::set_boxed_logger(Box::new(logger::Logger::new(tui.sender())))
+    // Init the global logger first, so problems loading saved state are reported
+    let (sender, events) = std::sync::mpsc::sync_channel(100);
+    log::set_boxed_logger(Box::new(logger::Logger::new(sender.clone())))
         .expect("Unable to create global logger");
     log::set_max_level(log::LevelFilter::Warn);
 
-    // Start all the slack connections first, because we can't do the Discord stuff fully async
-    if let Some(slack) = config.slack {
-        for c in slack {
-            let sender = tui.sender();
-            thread::spawn(move || {
-                if let Err(err) = SlackConn::create_on(&c.token, sender.clone()) {
-                    error!("Failed to create slack connection: {}\n{:#?}", err, c);
-                }
-            });
-        }
+    let tui = tui::Tui::new(&config.client, sender, events);
+
+    // Slack connections block on their startup requests, so each waits on a thread of its own
+    for (token, transport, app_token) in slack_accounts {
+        let sender = tui.sender();
+        thread::spawn(move || {
+            if let Err(err) = SlackConn::create_on(
+                &token,
+                transport,
+                app_token.as_ref().map(String::as_str),
+                sender.clone(),
+            ) {
+                error!("Failed to create slack connection: {}", err);
+            }
+        });
     }
 
-    if let (Some(discord_token), Some(discord)) = (config.discord_token, config.discord) {
+    if let (Some(discord_token), Some(discord)) = (discord_token, config.discord) {
         for d in discord {
-            let sender = tui.sender();
-            let token = discord_token.clone();
-            thread::spawn(move || {
-                let _ = DiscordConn::create_on(&token, sender.clone(), &d.name);
-            });
+            runtime::spawn(DiscordConn::create_on(
+                discord_token.clone(),
+                tui.sender(),
+                d.name,
+            )).forget();
         }
     }
 
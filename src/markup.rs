@@ -0,0 +1,345 @@
+//! Parsing of the lightweight markup that chat services use in message text
+
+/// Which flavor of markup a message is written in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// Shown exactly as written
+    Plain,
+    /// Slack's mrkdwn: `*bold*`, `_italic_`, `~strike~`
+    Slack,
+    /// Discord's markdown: `**bold**`, `*italic*`, `__underline__`, `~~strike~~`
+    Discord,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+    pub code: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mark {
+    Bold,
+    Italic,
+    Underline,
+    Strike,
+}
+
+impl Style {
+    fn with(mut self, mark: Mark) -> Self {
+        match mark {
+            Mark::Bold => self.bold = true,
+            Mark::Italic => self.italic = true,
+            Mark::Underline => self.underline = true,
+            Mark::Strike => self.strike = true,
+        }
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    pub text: String,
+    pub style: Style,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    /// Ordinary text, which can be reflowed
+    Text,
+    /// Text drawn with a gutter to its left
+    Quote,
+    /// Preformatted text that keeps its whitespace and line breaks
+    Code,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    pub kind: LineKind,
+    pub spans: Vec<Span>,
+}
+
+const FENCE: &str = "```";
+
+const SLACK_MARKS: &[(&str, Mark)] = &[
+    ("*", Mark::Bold),
+    ("_", Mark::Italic),
+    ("~", Mark::Strike),
+];
+// Longer delimiters come first so that `**` isn't read as two `*`
+const DISCORD_MARKS: &[(&str, Mark)] = &[
+    ("**", Mark::Bold),
+    ("__", Mark::Underline),
+    ("~~", Mark::Strike),
+    ("*", Mark::Italic),
+    ("_", Mark::Italic),
+];
+
+/// Split a message into lines of styled spans
+pub fn parse(text: &str, dialect: Dialect) -> Vec<Line> {
+    let mut lines = Vec::new();
+    if dialect == Dialect::Plain {
+        for line in text.lines() {
+            lines.push(Line {
+                kind: LineKind::Text,
+                spans: vec![Span {
+                    text: line.to_string(),
+                    style: Style::default(),
+                }],
+            });
+        }
+        return lines;
+    }
+
+    let code_line = |text: &str| Line {
+        kind: LineKind::Code,
+        spans: vec![Span {
+            text: text.to_string(),
+            style: Style {
+                code: true,
+                ..Style::default()
+            },
+        }],
+    };
+
+    let mut in_code = false;
+    // `>>>` quotes everything after it
+    let mut quote_rest = false;
+    for raw in text.lines() {
+        let mut rest = raw;
+        let mut had_fence = false;
+        // Fences don't have to be on their own line, Slack happily sends "```like this```"
+        while let Some(fence) = rest.find(FENCE) {
+            let before = &rest[..fence];
+            if in_code {
+                if !before.is_empty() {
+                    lines.push(code_line(before));
+                }
+            } else if !before.trim().is_empty() {
+                lines.push(text_line(before, dialect, &mut quote_rest));
+            }
+            in_code = !in_code;
+            had_fence = true;
+            rest = &rest[fence + FENCE.len()..];
+            // Discord puts a language name after the opening fence
+            if in_code
+                && dialect == Dialect::Discord
+                && !rest.contains(FENCE)
+                && !rest.contains(char::is_whitespace)
+            {
+                rest = "";
+            }
+        }
+        if in_code {
+            if !rest.is_empty() || !had_fence {
+                lines.push(code_line(rest));
+            }
+        } else if !rest.trim().is_empty() || !had_fence {
+            lines.push(text_line(rest, dialect, &mut quote_rest));
+        }
+    }
+    lines
+}
+
+fn text_line(text: &str, dialect: Dialect, quote_rest: &mut bool) -> Line {
+    let mut kind = if *quote_rest {
+        LineKind::Quote
+    } else {
+        LineKind::Text
+    };
+    let mut text = text;
+    if !*quote_rest {
+        if text.starts_with(">>>") {
+            *quote_rest = true;
+            kind = LineKind::Quote;
+            text = text[3..].trim_start();
+        } else if text == ">" || text.starts_with("> ") {
+            kind = LineKind::Quote;
+            text = text[1..].trim_start();
+        }
+    }
+    let mut spans = Vec::new();
+    parse_inline(text, dialect, Style::default(), &mut spans);
+    Line { kind, spans }
+}
+
+fn parse_inline(text: &str, dialect: Dialect, style: Style, spans: &mut Vec<Span>) {
+    let marks = match dialect {
+        Dialect::Plain => &[][..],
+        Dialect::Slack => SLACK_MARKS,
+        Dialect::Discord => DISCORD_MARKS,
+    };
+
+    let mut plain_start = 0;
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if rest.starts_with('`') {
+            if let Some(end) = rest[1..].find('`').filter(|end| *end > 0) {
+                push_span(spans, &text[plain_start..i], style);
+                push_span(
+                    spans,
+                    &rest[1..=end],
+                    Style {
+                        code: true,
+                        ..style
+                    },
+                );
+                i += end + 2;
+                plain_start = i;
+                continue;
+            }
+        } else if let Some(&(delimiter, mark)) = marks.iter().find(|(d, _)| rest.starts_with(d)) {
+            if let Some(close) = find_close(text, i, delimiter, dialect) {
+                push_span(spans, &text[plain_start..i], style);
+                parse_inline(
+                    &text[i + delimiter.len()..close],
+                    dialect,
+                    style.with(mark),
+                    spans,
+                );
+                i = close + delimiter.len();
+                plain_start = i;
+                continue;
+            }
+            // Skip all of it, or the second `_` of a `__` that can't open would open italic
+            i += delimiter.len();
+            continue;
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    push_span(spans, &text[plain_start..], style);
+}
+
+fn push_span(spans: &mut Vec<Span>, text: &str, style: Style) {
+    if text.is_empty() {
+        return;
+    }
+    match spans.last_mut() {
+        Some(ref mut last) if last.style == style => last.text.push_str(text),
+        _ => spans.push(Span {
+            text: text.to_string(),
+            style,
+        }),
+    }
+}
+
+/// Where the delimiter opened at `open` closes, if it does
+fn find_close(text: &str, open: usize, delimiter: &str, dialect: Dialect) -> Option<usize> {
+    // Slack only styles whole words, so snake_case and 2*3*4 stay as they are
+    let word_boundaries = dialect == Dialect::Slack || delimiter.starts_with('_');
+    let is_word = |c: Option<char>| c.map_or(false, char::is_alphanumeric);
+
+    let inner_start = open + delimiter.len();
+    let after_open = text[inner_start..].chars().next();
+    if after_open.map_or(true, char::is_whitespace)
+        || (word_boundaries && is_word(text[..open].chars().next_back()))
+    {
+        return None;
+    }
+
+    let mut search_from = inner_start;
+    while let Some(offset) = text[search_from..].find(delimiter) {
+        let close = search_from + offset;
+        let before = text[..close].chars().next_back();
+        let after = text[close + delimiter.len()..].chars().next();
+        if close > inner_start
+            && !before.map_or(true, char::is_whitespace)
+            && !(word_boundaries && is_word(after))
+        {
+            return Some(close);
+        }
+        search_from = close + delimiter.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, style: Style) -> Span {
+        Span {
+            text: text.to_string(),
+            style,
+        }
+    }
+
+    fn line(kind: LineKind, text: &str) -> Line {
+        let style = Style {
+            code: kind == LineKind::Code,
+            ..Style::default()
+        };
+        Line {
+            kind,
+            spans: vec![span(text, style)],
+        }
+    }
+
+    fn spans(text: &str, dialect: Dialect) -> Vec<Span> {
+        parse(text, dialect).remove(0).spans
+    }
+
+    #[test]
+    fn fenced_code() {
+        assert_eq!(
+            parse("before\n```\nlet x = 1;\n  indented\n```\nafter", Dialect::Slack),
+            [
+                line(LineKind::Text, "before"),
+                line(LineKind::Code, "let x = 1;"),
+                line(LineKind::Code, "  indented"),
+                line(LineKind::Text, "after"),
+            ]
+        );
+        assert_eq!(
+            parse("```like *this*```", Dialect::Slack),
+            [line(LineKind::Code, "like *this*")]
+        );
+        assert_eq!(
+            parse("```rust\nfn main() {}\n```", Dialect::Discord),
+            [line(LineKind::Code, "fn main() {}")]
+        );
+    }
+
+    #[test]
+    fn quotes() {
+        assert_eq!(
+            parse("intro\n>>> quoted\nstill quoted", Dialect::Slack),
+            [
+                line(LineKind::Text, "intro"),
+                line(LineKind::Quote, "quoted"),
+                line(LineKind::Quote, "still quoted"),
+            ]
+        );
+        assert_eq!(
+            parse("> one\nnot>quoted", Dialect::Discord),
+            [
+                line(LineKind::Quote, "one"),
+                line(LineKind::Text, "not>quoted"),
+            ]
+        );
+    }
+
+    #[test]
+    fn word_boundaries() {
+        let plain = Style::default();
+        let bold = Style {
+            bold: true,
+            ..plain
+        };
+        assert_eq!(spans("snake_case_name", Dialect::Slack), [span("snake_case_name", plain)]);
+        assert_eq!(spans("2*3*4", Dialect::Slack), [span("2*3*4", plain)]);
+        assert_eq!(
+            spans("*bold* text", Dialect::Slack),
+            [span("bold", bold), span(" text", plain)]
+        );
+        assert_eq!(spans("snake__case__x", Dialect::Discord), [span("snake__case__x", plain)]);
+        assert_eq!(
+            spans("a**b**c", Dialect::Discord),
+            [span("a", plain), span("b", bold), span("c", plain)]
+        );
+    }
+}
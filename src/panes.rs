@@ -0,0 +1,303 @@
+//! Splitting the message area into panes, each showing a channel of its own
+//!
+//! The layout is a tree of splits with panes at the leaves. Panes are known by an id that stays
+//! the same while other panes come and go, so whatever is shown in each can be kept elsewhere.
+
+/// A region of the terminal, 1-based like `termion::cursor::Goto`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rect {
+    pub x: u16,
+    pub y: u16,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    /// Side by side, with a line between them
+    Vertical,
+    /// One above the other
+    Horizontal,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Layout {
+    Pane(usize),
+    Split {
+        direction: Direction,
+        /// Percentage of the space that goes to `first`
+        ratio: u16,
+        first: Box<Layout>,
+        second: Box<Layout>,
+    },
+}
+
+const MIN_RATIO: u16 = 10;
+const MAX_RATIO: u16 = 90;
+
+impl Default for Layout {
+    fn default() -> Self {
+        Layout::Pane(0)
+    }
+}
+
+impl Layout {
+    /// Pane ids, left to right and top to bottom
+    pub fn panes(&self) -> Vec<usize> {
+        match *self {
+            Layout::Pane(id) => vec![id],
+            Layout::Split {
+                ref first,
+                ref second,
+                ..
+            } => {
+                let mut panes = first.panes();
+                panes.extend(second.panes());
+                panes
+            }
+        }
+    }
+
+    /// Split pane `id` in two, putting `new` after it
+    pub fn split(&mut self, id: usize, direction: Direction, new: usize) {
+        match *self {
+            Layout::Pane(pane) if pane == id => {
+                *self = Layout::Split {
+                    direction,
+                    ratio: 50,
+                    first: Box::new(Layout::Pane(id)),
+                    second: Box::new(Layout::Pane(new)),
+                };
+            }
+            Layout::Pane(_) => {}
+            Layout::Split {
+                ref mut first,
+                ref mut second,
+                ..
+            } => {
+                first.split(id, direction, new);
+                second.split(id, direction, new);
+            }
+        }
+    }
+
+    /// Remove pane `id`, giving its space to whatever it was split from. The last pane can't be
+    /// closed, so this returns whether anything changed.
+    pub fn close(&mut self, id: usize) -> bool {
+        let replacement = match *self {
+            Layout::Pane(_) => return false,
+            Layout::Split {
+                ref mut first,
+                ref mut second,
+                ..
+            } => {
+                if **first == Layout::Pane(id) {
+                    (**second).clone()
+                } else if **second == Layout::Pane(id) {
+                    (**first).clone()
+                } else {
+                    return first.close(id) || second.close(id);
+                }
+            }
+        };
+        *self = replacement;
+        true
+    }
+
+    /// Grow pane `id` by `delta` percent of the split it's in, or shrink it if that's negative
+    pub fn resize(&mut self, id: usize, delta: i16) -> bool {
+        match *self {
+            Layout::Pane(_) => false,
+            Layout::Split {
+                ref mut ratio,
+                ref mut first,
+                ref mut second,
+                ..
+            } => {
+                if first.resize(id, delta) || second.resize(id, delta) {
+                    return true;
+                }
+                let delta = if **first == Layout::Pane(id) {
+                    delta
+                } else if **second == Layout::Pane(id) {
+                    -delta
+                } else {
+                    return false;
+                };
+                let resized = (*ratio as i16 + delta).max(MIN_RATIO as i16) as u16;
+                *ratio = resized.min(MAX_RATIO);
+                true
+            }
+        }
+    }
+
+    /// Pull every split back within the ratios resizing allows, for layouts read from a file
+    pub fn clamp_ratios(&mut self) {
+        if let Layout::Split {
+            ref mut ratio,
+            ref mut first,
+            ref mut second,
+            ..
+        } = *self
+        {
+            *ratio = (*ratio).max(MIN_RATIO).min(MAX_RATIO);
+            first.clamp_ratios();
+            second.clamp_ratios();
+        }
+    }
+
+    /// Where each pane goes in `area`. Vertical splits leave a column between the panes for a
+    /// separator.
+    pub fn place(&self, area: Rect) -> Vec<(usize, Rect)> {
+        match *self {
+            Layout::Pane(id) => vec![(id, area)],
+            Layout::Split {
+                direction,
+                ratio,
+                ref first,
+                ref second,
+            } => {
+                let (first_area, second_area) = divide(direction, ratio, area);
+                let mut placed = first.place(first_area);
+                placed.extend(second.place(second_area));
+                placed
+            }
+        }
+    }
+
+    /// The columns left between side by side panes
+    pub fn separators(&self, area: Rect) -> Vec<Rect> {
+        match *self {
+            Layout::Pane(_) => Vec::new(),
+            Layout::Split {
+                direction,
+                ratio,
+                ref first,
+                ref second,
+            } => {
+                let (first_area, second_area) = divide(direction, ratio, area);
+                let mut separators = first.separators(first_area);
+                separators.extend(second.separators(second_area));
+                if direction == Direction::Vertical {
+                    separators.push(Rect {
+                        x: first_area.x + first_area.width,
+                        width: 1,
+                        ..area
+                    });
+                }
+                separators
+            }
+        }
+    }
+}
+
+fn divide(direction: Direction, ratio: u16, area: Rect) -> (Rect, Rect) {
+    match direction {
+        Direction::Vertical => {
+            let available = area.width.saturating_sub(1);
+            let width = (u32::from(available) * u32::from(ratio) / 100) as u16;
+            let second = Rect {
+                x: area.x + width + 1,
+                width: available - width,
+                ..area
+            };
+            (Rect { width, ..area }, second)
+        }
+        Direction::Horizontal => {
+            let height = (u32::from(area.height) * u32::from(ratio) / 100) as u16;
+            let second = Rect {
+                y: area.y + height,
+                height: area.height - height,
+                ..area
+            };
+            (Rect { height, ..area }, second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 21,
+        y: 2,
+        width: 81,
+        height: 40,
+    };
+
+    fn rect(x: u16, y: u16, width: u16, height: u16) -> Rect {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    #[test]
+    fn splits_share_the_area() {
+        let mut layout = Layout::default();
+        layout.split(0, Direction::Vertical, 1);
+        layout.split(1, Direction::Horizontal, 2);
+        assert_eq!(layout.panes(), [0, 1, 2]);
+        assert_eq!(
+            layout.place(AREA),
+            [
+                (0, rect(21, 2, 40, 40)),
+                (1, rect(62, 2, 40, 20)),
+                (2, rect(62, 22, 40, 20)),
+            ]
+        );
+        assert_eq!(layout.separators(AREA), [rect(61, 2, 1, 40)]);
+    }
+
+    #[test]
+    fn closing_gives_space_back() {
+        let mut layout = Layout::default();
+        layout.split(0, Direction::Horizontal, 1);
+        layout.split(0, Direction::Vertical, 2);
+        assert!(layout.close(0));
+        assert_eq!(layout.panes(), [2, 1]);
+        assert!(layout.close(2));
+        assert!(!layout.close(1));
+        assert_eq!(layout, Layout::Pane(1));
+    }
+
+    #[test]
+    fn resizing_stays_in_bounds() {
+        let mut layout = Layout::default();
+        layout.split(0, Direction::Vertical, 1);
+        assert!(layout.resize(1, 20));
+        assert_eq!(layout.place(AREA)[0].1.width, 24);
+        for _ in 0..10 {
+            layout.resize(0, 20);
+        }
+        assert_eq!(layout.place(AREA)[1].1.width, 8);
+        assert!(!layout.resize(5, 10));
+    }
+
+    #[test]
+    fn saved_ratios_are_clamped() {
+        let mut layout = Layout::Split {
+            direction: Direction::Horizontal,
+            ratio: 250,
+            first: Box::new(Layout::Pane(0)),
+            second: Box::new(Layout::Split {
+                direction: Direction::Vertical,
+                ratio: 0,
+                first: Box::new(Layout::Pane(1)),
+                second: Box::new(Layout::Pane(2)),
+            }),
+        };
+        layout.clamp_ratios();
+        assert_eq!(
+            layout.place(AREA),
+            [
+                (0, rect(21, 2, 81, 36)),
+                (1, rect(21, 38, 8, 4)),
+                (2, rect(30, 38, 72, 4)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! The event loop every connection shares
+//!
+//! Web API calls, websockets and timers for every account run on one reactor thread, instead of a
+//! thread per request and a reactor per connection. HTTP requests also wait their turn in a queue,
+//! so that a large workspace can't open hundreds of connections at once.
+use futures::sync::{mpsc, oneshot};
+use futures::{future, Future, IntoFuture, Stream};
+use std::sync::mpsc as std_mpsc;
+use std::thread;
+use tokio_core::reactor::{Core, Handle, Remote};
+
+/// How many HTTP requests can be in flight at once, across every account
+const MAX_REQUESTS: usize = 8;
+
+type Request = Box<Future<Item = (), Error = ()> + Send>;
+
+lazy_static! {
+    static ref REMOTE: Remote = start();
+    static ref REQUESTS: mpsc::UnboundedSender<Request> = {
+        let (sender, receiver) = mpsc::unbounded::<Request>();
+        REMOTE.spawn(|_| receiver.buffer_unordered(MAX_REQUESTS).for_each(|()| Ok(())));
+        sender
+    };
+    pub static ref CLIENT: ::reqwest::async::Client = ::reqwest::async::Client::builder()
+        .max_idle_per_host(MAX_REQUESTS)
+        .build()
+        .expect("Unable to create an HTTP client");
+}
+
+fn start() -> Remote {
+    let (sender, receiver) = std_mpsc::channel();
+    thread::Builder::new()
+        .name(String::from("runtime"))
+        .spawn(move || {
+            let mut core = Core::new().expect("Unable to start the event loop");
+            let _ = sender.send(core.remote());
+            let _ = core.run(future::empty::<(), ()>());
+        }).expect("Unable to start the event loop");
+    receiver.recv().expect("The event loop failed to start")
+}
+
+/// Run `future` on the event loop. It's cancelled if the handle is dropped without being waited on
+/// or `forget`ten.
+pub fn spawn<F>(future: F) -> oneshot::SpawnHandle<F::Item, F::Error>
+where
+    F: Future + Send + 'static,
+    F::Item: Send,
+    F::Error: Send,
+{
+    oneshot::spawn(future, &*REMOTE)
+}
+
+/// Run a future that has to stay on the event loop's thread, like a websocket, which is built from
+/// the loop's `Handle`
+pub fn spawn_local<F, R>(f: F)
+where
+    F: FnOnce(&Handle) -> R + Send + 'static,
+    R: IntoFuture<Item = (), Error = ()>,
+    R::Future: 'static,
+{
+    REMOTE.spawn(f)
+}
+
+/// Why a request held back by `limit` didn't produce anything
+#[derive(Debug)]
+pub enum LimitError<E> {
+    Failed(E),
+    /// The request was dropped without running, because the event loop has stopped
+    Canceled,
+}
+
+impl<E: ::std::fmt::Display> ::std::fmt::Display for LimitError<E> {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        match self {
+            LimitError::Failed(e) => write!(f, "{}", e),
+            LimitError::Canceled => write!(f, "The request queue went away"),
+        }
+    }
+}
+
+/// Hold an HTTP request back until fewer than `MAX_REQUESTS` others are in flight
+pub fn limit<F>(request: F) -> impl Future<Item = F::Item, Error = LimitError<F::Error>>
+where
+    F: Future + Send + 'static,
+    F::Item: Send,
+    F::Error: Send,
+{
+    let (sender, receiver) = oneshot::channel();
+    let request = request.then(move |result| {
+        let _ = sender.send(result);
+        Ok(())
+    });
+    let _ = REQUESTS.unbounded_send(Box::new(request));
+    receiver.then(|result| match result {
+        Ok(result) => result.map_err(LimitError::Failed),
+        Err(oneshot::Canceled) => Err(LimitError::Canceled),
+    })
+}
@@ -0,0 +1,100 @@
+//! Tokens, and the files that might end up holding them
+//!
+//! The config file can name a token indirectly instead of holding it in plain text, and everything
+//! we write to disk is kept readable by only the user.
+use std::fs::{self, OpenOptions, Permissions};
+use std::io;
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Work out a token from the config, which is either a literal, a `$VARIABLE` to read it from, or
+/// a command that prints it, like `pass show slack/work`
+pub fn resolve(value: Option<&str>, command: Option<&str>) -> Result<Option<String>, String> {
+    match (value, command) {
+        (Some(_), Some(_)) => Err(String::from("give either a token or a command, not both")),
+        (None, Some(command)) => run(command).map(Some),
+        (Some(value), None) if value.starts_with('$') => {
+            let name = value[1..].trim_matches(|c| c == '{' || c == '}');
+            ::std::env::var(name)
+                .map(Some)
+                .map_err(|_| format!("environment variable {} is not set", name))
+        }
+        (Some(value), None) => Ok(Some(value.to_string())),
+        (None, None) => Ok(None),
+    }
+}
+
+fn run(command: &str) -> Result<String, String> {
+    // The command gets the terminal, so it can prompt for a password and show its own errors
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("couldn't run `{}`: {}", command, e))?;
+    if !output.status.success() {
+        return Err(format!("`{}` failed with {}", command, output.status));
+    }
+    // Password managers print the secret on the first line and may add more after it
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    match stdout.lines().next().map(str::trim) {
+        Some(token) if !token.is_empty() => Ok(token.to_string()),
+        _ => Err(format!("`{}` didn't print anything", command)),
+    }
+}
+
+/// Options for creating a file only the user can read or write
+pub fn private() -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.mode(0o600);
+    options
+}
+
+/// Take away group and world access from a file that already exists
+pub fn restrict(path: &Path) -> io::Result<()> {
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        fs::set_permissions(path, Permissions::from_mode(mode & 0o700))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literal() {
+        assert_eq!(resolve(Some("xoxb-1"), None), Ok(Some(String::from("xoxb-1"))));
+        assert_eq!(resolve(None, None), Ok(None));
+    }
+
+    #[test]
+    fn environment_variables() {
+        ::std::env::set_var("OMNICHAT_TEST_TOKEN", "xoxb-env");
+        let expected = Ok(Some(String::from("xoxb-env")));
+        assert_eq!(resolve(Some("$OMNICHAT_TEST_TOKEN"), None), expected);
+        assert_eq!(resolve(Some("${OMNICHAT_TEST_TOKEN}"), None), expected);
+        assert!(resolve(Some("$OMNICHAT_TEST_UNSET"), None).is_err());
+    }
+
+    #[test]
+    fn value_and_command() {
+        assert!(resolve(Some("xoxb-1"), Some("echo xoxb-2")).is_err());
+    }
+
+    #[test]
+    fn command_first_line() {
+        let token = resolve(None, Some("printf '  xoxb-cmd  \\nsecond line\\n'"));
+        assert_eq!(token, Ok(Some(String::from("xoxb-cmd"))));
+        assert!(resolve(None, Some("true")).is_err());
+    }
+
+    #[test]
+    fn failing_command() {
+        let err = resolve(None, Some("exit 3")).unwrap_err();
+        assert!(err.ends_with("exit status: 3"), "{}", err);
+    }
+}
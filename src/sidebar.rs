@@ -0,0 +1,226 @@
+//! Grouping, ordering and filtering the channels down the left side
+use conn::{ChannelKind, DateTime};
+use std::collections::HashSet;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Starred,
+    Channels,
+    Private,
+    Dms,
+    Apps,
+}
+
+impl Section {
+    pub fn of(kind: ChannelKind) -> Section {
+        match kind {
+            ChannelKind::Public => Section::Channels,
+            ChannelKind::Private => Section::Private,
+            ChannelKind::Direct => Section::Dms,
+            ChannelKind::App => Section::Apps,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Section> {
+        match name {
+            "starred" => Some(Section::Starred),
+            "channels" => Some(Section::Channels),
+            "private" => Some(Section::Private),
+            "dms" => Some(Section::Dms),
+            "apps" => Some(Section::Apps),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Section::Starred => "Starred",
+            Section::Channels => "Channels",
+            Section::Private => "Private",
+            Section::Dms => "Direct messages",
+            Section::Apps => "Apps",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sort {
+    Alphabetical,
+    /// Most recent message first
+    Activity,
+}
+
+/// What the sidebar needs to know about each of a server's channels
+pub struct Item<'a> {
+    pub name: &'a str,
+    pub section: Section,
+    pub unreads: usize,
+    pub last_activity: Option<DateTime>,
+    /// Whether there's anything in it at all, messages or unreads or a draft
+    pub active: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Row {
+    /// Collapsed sections say how many unreads they're hiding
+    Header {
+        section: Section,
+        collapsed: bool,
+        unreads: usize,
+    },
+    /// An index into the items
+    Channel(usize),
+}
+
+pub struct Sidebar {
+    /// In the order they're drawn. Channels in a section that isn't listed go under Channels,
+    /// which is always shown.
+    pub sections: Vec<Section>,
+    pub sort: Sort,
+    pub hide_inactive: bool,
+    pub collapsed: HashSet<Section>,
+    pub width: u16,
+}
+
+impl Sidebar {
+    /// Everything to draw, top to bottom. The current channel is always in there, even if its
+    /// section is collapsed or it would be hidden for being inactive.
+    pub fn rows(&self, items: &[Item], current: usize) -> Vec<Row> {
+        let mut sections = self.sections.clone();
+        if !sections.contains(&Section::Channels) {
+            sections.push(Section::Channels);
+        }
+        let section_of = |item: &Item| {
+            if sections.contains(&item.section) {
+                item.section
+            } else {
+                Section::Channels
+            }
+        };
+
+        let mut rows = Vec::new();
+        for &section in &sections {
+            let mut members = (0..items.len())
+                .filter(|&i| section_of(&items[i]) == section)
+                .filter(|&i| i == current || items[i].active || !self.hide_inactive)
+                .collect::<Vec<_>>();
+            if members.is_empty() {
+                continue;
+            }
+            match self.sort {
+                Sort::Alphabetical => members.sort_by_key(|&i| items[i].name),
+                Sort::Activity => members.sort_by(|&a, &b| {
+                    items[b]
+                        .last_activity
+                        .cmp(&items[a].last_activity)
+                        .then(items[a].name.cmp(items[b].name))
+                }),
+            }
+            let collapsed = self.collapsed.contains(&section);
+            rows.push(Row::Header {
+                section,
+                collapsed,
+                unreads: if collapsed {
+                    members.iter().map(|&i| items[i].unreads).sum()
+                } else {
+                    0
+                },
+            });
+            rows.extend(
+                members
+                    .into_iter()
+                    .filter(|&i| !collapsed || i == current)
+                    .map(Row::Channel),
+            );
+        }
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, section: Section, active: bool) -> Item {
+        Item {
+            name,
+            section,
+            unreads: if active { 1 } else { 0 },
+            last_activity: None,
+            active,
+        }
+    }
+
+    fn sidebar() -> Sidebar {
+        Sidebar {
+            sections: vec![Section::Dms, Section::Channels],
+            sort: Sort::Alphabetical,
+            hide_inactive: false,
+            collapsed: HashSet::new(),
+            width: 20,
+        }
+    }
+
+    #[test]
+    fn groups_into_sections() {
+        let items = [
+            item("random", Section::Channels, true),
+            item("@bob", Section::Dms, false),
+            item("secret", Section::Private, false),
+            item("general", Section::Channels, false),
+        ];
+        let rows = sidebar().rows(&items, 0);
+        assert_eq!(
+            rows,
+            [
+                Row::Header {
+                    section: Section::Dms,
+                    collapsed: false,
+                    unreads: 0
+                },
+                Row::Channel(1),
+                Row::Header {
+                    section: Section::Channels,
+                    collapsed: false,
+                    unreads: 0
+                },
+                Row::Channel(3),
+                Row::Channel(0),
+                Row::Channel(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn collapsing_and_hiding_keep_the_current_channel() {
+        let items = [
+            item("general", Section::Channels, false),
+            item("random", Section::Channels, true),
+            item("@bob", Section::Dms, true),
+        ];
+        let mut sidebar = sidebar();
+        sidebar.hide_inactive = true;
+        sidebar.collapsed.insert(Section::Dms);
+        let channels = |rows: Vec<Row>| {
+            rows.into_iter()
+                .filter_map(|row| match row {
+                    Row::Channel(i) => Some(i),
+                    Row::Header { .. } => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(channels(sidebar.rows(&items, 1)), [1]);
+        assert_eq!(channels(sidebar.rows(&items, 2)), [2, 1]);
+        assert_eq!(channels(sidebar.rows(&items, 0)), [0, 1]);
+        assert_eq!(
+            sidebar.rows(&items, 1)[0],
+            Row::Header {
+                section: Section::Dms,
+                collapsed: true,
+                unreads: 1
+            }
+        );
+    }
+}
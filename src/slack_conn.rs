@@ -1,16 +1,22 @@
 use bimap::BiMap;
-use conn::{Conn, Event, IString, Message};
+use conn::{ChannelKind, Conn, ConnectionState, Event, IString, Message};
 use futures::sync::mpsc;
-use futures::{Future, Sink, Stream};
+use futures::sync::oneshot::SpawnHandle;
+use futures::{future, Future, Sink, Stream};
 use regex::Regex;
+use runtime::LimitError;
+use slack_scheduler::{is_idempotent, Scheduler};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::mpsc::SyncSender;
-use std::sync::{Arc, RwLock};
-use std::thread;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tokio_core::reactor::Handle;
+use tokio_timer::Delay;
 
 lazy_static! {
     pub static ref MENTION_REGEX: Regex = Regex::new(r"<@[A-Z0-9]{9}>").unwrap();
     pub static ref CHANNEL_REGEX: Regex = Regex::new(r"<#[A-Z0-9]{9}\|(?P<n>.*?)>").unwrap();
-    pub static ref CLIENT: ::reqwest::Client = ::reqwest::Client::new();
+    pub static ref EMOJI_REGEX: Regex = Regex::new(r":(?P<name>[a-z0-9_+'-]+):").unwrap();
 }
 
 #[derive(Debug)]
@@ -18,72 +24,241 @@ pub enum Error {
     Slack(String),
     CannotParse(::serde_json::error::Error, String),
     Reqwest(::reqwest::Error),
+    RateLimited(&'static str, Duration),
     Other(String),
 }
 
-use std::any::Any;
-impl From<Box<dyn Any + Send>> for Error {
-    fn from(e: Box<dyn Any + Send>) -> Error {
-        Error::Other(format!("{:?}", e))
-    }
-}
-
 impl ::std::fmt::Display for Error {
     fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
         match self {
             Error::Slack(ref e) => write!(f, "{}", e),
             Error::CannotParse(ref json, ref e) => write!(f, "{}\n{}", json, e),
             Error::Reqwest(ref e) => write!(f, "{}", e),
+            Error::RateLimited(method, retry_after) => write!(
+                f,
+                "{} is rate limited for another {}s",
+                method,
+                retry_after.as_secs()
+            ),
             Error::Other(ref e) => write!(f, "{}", e),
         }
     }
 }
 
-use std::thread::JoinHandle;
-fn get_slack<T, R>(endpoint: &'static str, token: &str, request: T) -> JoinHandle<Result<R, Error>>
+/// How a Slack account receives events
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    /// The Real Time Messaging API, which Slack no longer issues tokens for to new apps
+    Rtm,
+    /// Events API payloads over a WebSocket, which needs an app-level token as well
+    SocketMode,
+}
+
+impl Default for Transport {
+    fn default() -> Self {
+        Transport::Rtm
+    }
+}
+
+/// Turn the body of a Web API response into `R`, or the error Slack reported instead
+pub fn parse_response<R>(body: String) -> Result<R, Error>
 where
-    T: ::serde::Serialize + Send + 'static,
-    R: ::serde::de::DeserializeOwned + Send + 'static,
+    R: ::serde::de::DeserializeOwned,
 {
     use slack::http::SlackError;
-    let token = token.to_string();
-    thread::spawn(move || {
-        let url = format!(
-            "https://slack.com/api/{}?token={}&{}",
-            endpoint,
-            token,
-            ::serde_urlencoded::to_string(request).unwrap_or_default()
-        ).parse::<::reqwest::Url>()
-        .unwrap();
-
-        CLIENT
-            .get(url.clone())
-            .send()
-            .map_err(|e| Error::Reqwest(e))
-            .and_then(|mut response| response.text().map_err(|e| Error::Reqwest(e)))
-            .and_then(|body| {
-                match ::serde_json::from_str::<SlackError>(&body)
-                    .map_err(|e| Error::CannotParse(e, body.clone()))
-                {
-                    Ok(SlackError { ok: true, .. }) => ::serde_json::from_str::<R>(&body)
-                        .map_err(|e| Error::CannotParse(e, body.clone())),
-                    Ok(SlackError { ok: false, error }) => Err(Error::Slack(
-                        error.unwrap_or_else(|| String::from("no error given")),
-                    )),
-                    Err(e) => Err(e),
+    match ::serde_json::from_str::<SlackError>(&body)
+        .map_err(|e| Error::CannotParse(e, body.clone()))
+    {
+        Ok(SlackError { ok: true, .. }) => {
+            ::serde_json::from_str::<R>(&body).map_err(|e| Error::CannotParse(e, body.clone()))
+        }
+        Ok(SlackError { ok: false, error }) => Err(Error::Slack(
+            error.unwrap_or_else(|| String::from("no error given")),
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// How many times a call is tried before giving up on it
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Why one attempt at a call didn't get a response body back
+enum Failure {
+    RateLimited(Duration),
+    /// Worth trying again, if the call is safe to repeat
+    Transient(Error),
+    Fatal(Error),
+}
+
+/// A token, and the rate limits of the workspace it belongs to
+#[derive(Clone)]
+struct Api {
+    token: Arc<String>,
+    scheduler: Arc<Scheduler>,
+    /// Where the Web API methods live, which tests point somewhere else
+    base_url: Arc<String>,
+}
+
+impl Api {
+    fn new(token: &str) -> Self {
+        Api {
+            token: Arc::new(token.to_string()),
+            scheduler: Arc::new(Scheduler::default()),
+            base_url: Arc::new(String::from("https://slack.com/api")),
+        }
+    }
+
+    /// Start calling a Web API method on the shared event loop. Dropping the handle cancels it.
+    fn call<T, R>(&self, method: &'static str, request: T) -> SpawnHandle<R, Error>
+    where
+        T: ::serde::Serialize,
+        R: ::serde::de::DeserializeOwned + Send + 'static,
+    {
+        let form = ::serde_urlencoded::to_string(request).unwrap_or_default();
+        ::runtime::spawn(self.post_form(method, form))
+    }
+
+    /// Call `method`, retrying when Slack is rate limiting us, and for calls that are safe to
+    /// repeat, when the network or Slack itself has trouble. This has to run on the event loop.
+    fn post_form<R>(
+        &self,
+        method: &'static str,
+        form: String,
+    ) -> Box<Future<Item = R, Error = Error> + Send>
+    where
+        R: ::serde::de::DeserializeOwned + Send + 'static,
+    {
+        use futures::future::{loop_fn, Loop};
+        type Step = Box<Future<Item = Loop<String, u32>, Error = Error> + Send>;
+
+        let api = self.clone();
+        let body = loop_fn(1, move |attempt| {
+            let scheduler = Arc::clone(&api.scheduler);
+            let can_retry = attempt < MAX_ATTEMPTS;
+            api.attempt(method, form.clone()).then(move |result| -> Step {
+                let retry = |pause: Duration| -> Step {
+                    scheduler.retrying();
+                    Box::new(
+                        Delay::new(Instant::now() + pause)
+                            .map(move |()| Loop::Continue(attempt + 1))
+                            .map_err(|e| Error::Other(e.to_string())),
+                    )
+                };
+                match result {
+                    Ok(body) => Box::new(future::ok(Loop::Break(body))),
+                    // Slack didn't act on the call, and the scheduler holds the retry back for us
+                    Err(Failure::RateLimited(_)) if can_retry => retry(Duration::from_secs(0)),
+                    Err(Failure::RateLimited(retry_after)) => {
+                        Box::new(future::err(Error::RateLimited(method, retry_after)))
+                    }
+                    Err(Failure::Transient(_)) if can_retry && is_idempotent(method) => {
+                        retry(Duration::from_millis(500 << attempt))
+                    }
+                    Err(Failure::Transient(e)) | Err(Failure::Fatal(e)) => Box::new(future::err(e)),
                 }
             })
-    })
+        });
+        Box::new(body.and_then(parse_response))
+    }
+
+    /// The token goes in a header instead of the URL, where it would end up in proxy logs and in
+    /// every reqwest error we log. Every method accepts a form body, even the ones that also take
+    /// JSON.
+    fn attempt(
+        &self,
+        method: &'static str,
+        form: String,
+    ) -> impl Future<Item = String, Error = Failure> {
+        use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+        let api = self.clone();
+        Scheduler::wait_turn(&self.scheduler, method)
+            .map_err(|e| Failure::Fatal(Error::Other(e.to_string())))
+            .and_then(move |turn| {
+                let request = ::runtime::CLIENT
+                    .post(&format!("{}/{}", api.base_url, method))
+                    .bearer_auth(api.token.as_str())
+                    .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                    .body(form)
+                    .send()
+                    .and_then(|mut response| {
+                        let status = response.status();
+                        let retry_after = response
+                            .headers()
+                            .get(RETRY_AFTER)
+                            .and_then(|value| value.to_str().ok())
+                            .and_then(|value| value.parse().ok())
+                            .map_or(Duration::from_secs(30), Duration::from_secs);
+                        response.text().map(move |body| (status, retry_after, body))
+                    });
+                ::runtime::limit(request).then(move |result| {
+                    drop(turn);
+                    match result {
+                        Err(LimitError::Failed(e)) => Err(Failure::Transient(Error::Reqwest(e))),
+                        Err(e @ LimitError::Canceled) => {
+                            Err(Failure::Fatal(Error::Other(e.to_string())))
+                        }
+                        Ok((status, retry_after, _)) if status.as_u16() == 429 => {
+                            api.scheduler.rate_limited(method, retry_after);
+                            Err(Failure::RateLimited(retry_after))
+                        }
+                        Ok((status, ..)) if status.is_server_error() => Err(Failure::Transient(
+                            Error::Slack(format!("{} failed with {}", method, status)),
+                        )),
+                        Ok((_, _, body)) => Ok(body),
+                    }
+                })
+            })
+    }
+}
+
+/// Log the failure of a call that nothing is waiting on
+fn report_failure<R>(response: SpawnHandle<R, Error>, action: String)
+where
+    R: Send + 'static,
+{
+    ::runtime::spawn(
+        response
+            .map(|_| ())
+            .map_err(move |e| error!("Failed to {}: {}", action, e)),
+    ).forget();
+}
+
+/// Errors that mean the token itself is no good, so there's no point asking again with it
+const AUTH_ERRORS: &[&str] = &[
+    "invalid_auth",
+    "not_authed",
+    "account_inactive",
+    "token_revoked",
+    "token_expired",
+    "not_allowed_token_type",
+];
+
+fn is_auth_error(error: &Error) -> bool {
+    match error {
+        Error::Slack(ref e) => AUTH_ERRORS.contains(&e.as_str()),
+        _ => false,
+    }
+}
+
+/// Ask for a fresh Socket Mode WebSocket URL, which is only good for one connection
+fn open_socket_mode(app: &Api) -> impl Future<Item = String, Error = Error> {
+    use slack::http::apps::ConnectionsOpenResponse;
+    app.post_form("apps.connections.open", String::new())
+        .map(|response: ConnectionsOpenResponse| response.url)
 }
 
 struct Handler {
     channels: BiMap<::slack::ConversationId, IString>,
     users: BiMap<::slack::UserId, IString>,
     server_name: IString,
+    my_id: ::slack::UserId,
     my_name: IString,
     input_sender: ::futures::sync::mpsc::Sender<::websocket::OwnedMessage>,
     tui_sender: SyncSender<Event>,
     pending_messages: Vec<PendingMessage>,
+    /// Custom emoji that are just another name for some other emoji
+    emoji_aliases: HashMap<String, String>,
 }
 
 #[derive(Deserialize)]
@@ -96,12 +271,40 @@ struct MessageAck {
     ts: ::slack::Timestamp,
 }
 
+fn convert_file(file: ::slack::rtm::File) -> ::conn::File {
+    ::conn::File {
+        name: file.name.or(file.title).unwrap_or_default(),
+        kind: file.pretty_type.or(file.filetype),
+        size: file.size,
+        url: file.url_private,
+    }
+}
+
 struct PendingMessage {
     id: u32,
     channel: IString,
 }
 
 impl Handler {
+    fn send_state(&self, state: ConnectionState) {
+        let _ = self.tui_sender.send(Event::ConnectionChanged {
+            server: self.server_name.clone(),
+            state,
+        });
+    }
+
+    /// Follow `alias:` custom emoji to what they stand for, so they can be drawn as Unicode
+    fn resolve_emoji<'a>(&'a self, name: &'a str) -> &'a str {
+        let (base, tone) = match name.find("::") {
+            Some(i) => name.split_at(i),
+            None => (name, ""),
+        };
+        match self.emoji_aliases.get(base) {
+            Some(target) if tone.is_empty() => target,
+            _ => name,
+        }
+    }
+
     pub fn to_omni(
         &self,
         message: ::slack::rtm::Message,
@@ -113,54 +316,56 @@ impl Handler {
         if let ::slack::rtm::Message::ShRoomCreated(ref m) = message {
             error!("{:#?}", m);
         }
-        let (channel, user, mut text, ts, reactions) = match message {
+        let (channel, user, text, ts, reactions, attachments, files) = match message {
             Standard(MessageStandard {
                 channel,
                 user,
-                mut text,
+                text,
                 ts: Some(ts),
                 reactions,
+                attachments,
                 files,
+                blocks,
                 ..
             }) => {
                 let user = user.unwrap_or_else(|| "UNKNOWNUS".into());
-                for file in files.unwrap_or_default() {
-                    if text.is_empty() {
-                        text = file.url_private.unwrap_or_default();
-                    } else {
-                        text = format!("{}\n{}", text, file.url_private.unwrap_or_default());
-                    }
-                }
                 (
                     outer_channel.or(channel),
                     self.users
                         .get_right(&user)
                         .unwrap_or(&user.as_str().into())
                         .clone(),
-                    text,
+                    self.message_text(&text, blocks.as_ref()),
                     ts,
-                    reactions
-                        .iter()
-                        .map(|r| (r.name.as_str().into(), r.count.unwrap_or_default() as usize))
-                        .collect(),
+                    reactions,
+                    attachments,
+                    files,
                 )
             }
+            // Bots that only post attachments don't send any text at all
             BotMessage(MessageBotMessage {
                 channel,
-                username: Some(name),
-                text: Some(text),
+                username,
+                bot_id,
+                text,
                 ts: Some(ts),
                 reactions,
+                attachments,
+                blocks,
                 ..
             }) => (
                 outer_channel.or(channel),
-                name.into(),
-                text,
+                username
+                    .as_ref()
+                    .map(String::as_str)
+                    .or_else(|| bot_id.as_ref().map(|id| id.as_str()))
+                    .unwrap_or("bot")
+                    .into(),
+                self.message_text(&text.unwrap_or_default(), blocks.as_ref()),
                 ts,
-                reactions
-                    .iter()
-                    .map(|r| (r.name.as_str().into(), r.count.unwrap_or_default() as usize))
-                    .collect(),
+                reactions,
+                attachments,
+                None,
             ),
             SlackbotResponse(MessageSlackbotResponse {
                 channel,
@@ -175,17 +380,57 @@ impl Handler {
                     .get_right(&user)
                     .unwrap_or(&user.as_str().into())
                     .clone(),
-                text,
+                self.convert_text(&text),
                 ts,
-                reactions
-                    .iter()
-                    .map(|r| (r.name.as_str().into(), r.count.unwrap_or_default() as usize))
-                    .collect(),
+                reactions,
+                None,
+                None,
             ),
             _ => return None,
         };
 
-        text = text.replace("&amp;", "&");
+        let contents = ::conn::Body {
+            attachments: attachments
+                .unwrap_or_default()
+                .iter()
+                .map(|a| self.convert_attachment(a))
+                .collect(),
+            files: files
+                .unwrap_or_default()
+                .into_iter()
+                .map(convert_file)
+                .collect(),
+            text,
+        };
+        let reactions = reactions
+            .iter()
+            .map(|r| {
+                (
+                    self.resolve_emoji(&r.name).into(),
+                    r.count.unwrap_or_default() as usize,
+                )
+            })
+            .collect();
+
+        if let Some(channel) = channel.and_then(|c| self.channels.get_right(&c)) {
+            return Some(::conn::Message {
+                server: self.server_name.as_ref().into(),
+                channel: channel.clone(),
+                sender: user,
+                is_mention: contents.text.contains(self.my_name.as_ref()),
+                contents,
+                timestamp: ts.into(),
+                reactions,
+                dialect: ::markup::Dialect::Slack,
+            });
+        } else {
+            return None;
+        }
+    }
+
+    /// Turn Slack's escaped text with `<@U…>` style references into what a person would type
+    fn convert_text(&self, text: &str) -> String {
+        let mut text = text.replace("&amp;", "&");
         text = text.replace("&lt;", "<");
         text = text.replace("&gt;", ">");
 
@@ -200,18 +445,253 @@ impl Handler {
 
         text = CHANNEL_REGEX.replace_all(&text, "#$n").into_owned();
 
-        if let Some(channel) = channel.and_then(|c| self.channels.get_right(&c)) {
-            return Some(::conn::Message {
-                server: self.server_name.as_ref().into(),
-                channel: channel.clone(),
-                sender: user,
-                is_mention: text.contains(self.my_name.as_ref()),
-                contents: text,
-                timestamp: ts.into(),
-                reactions,
-            });
-        } else {
-            return None;
+        if !self.emoji_aliases.is_empty() {
+            text = EMOJI_REGEX
+                .replace_all(&text, |caps: &::regex::Captures| {
+                    format!(":{}:", self.resolve_emoji(&caps["name"]))
+                }).into_owned();
+        }
+        text
+    }
+
+    /// Blocks are the real content when a message has them, its text is only a fallback
+    fn message_text(&self, text: &str, blocks: Option<&Vec<::slack::rtm::Block>>) -> String {
+        blocks
+            .map(|blocks| self.convert_blocks(blocks))
+            .filter(|converted| !converted.trim().is_empty())
+            .unwrap_or_else(|| self.convert_text(text))
+    }
+
+    /// Lay out Block Kit content as mrkdwn, so it gets the same rendering as plain messages
+    fn convert_blocks(&self, blocks: &[::slack::rtm::Block]) -> String {
+        use slack::rtm::{Block, BlockElement, ContextElement, TextObject};
+
+        let text_object = |text: &TextObject| match text {
+            TextObject::Mrkdwn { text, .. } => self.convert_text(text),
+            TextObject::PlainText { text, .. } => text.clone(),
+        };
+        let element = |element: &BlockElement| match element {
+            BlockElement::Button { text, .. } => Some(format!("[{}]", text.text())),
+            BlockElement::Image { alt_text, .. } => Some(format!("[image: {}]", alt_text)),
+            BlockElement::Unknown => None,
+        };
+
+        let mut lines = Vec::new();
+        for block in blocks {
+            match block {
+                Block::Section {
+                    text,
+                    fields,
+                    accessory,
+                    ..
+                } => {
+                    lines.extend(text.iter().map(&text_object));
+                    lines.extend(fields.iter().flat_map(|f| f.iter()).map(&text_object));
+                    lines.extend(accessory.iter().filter_map(&element));
+                }
+                Block::Context { elements, .. } => lines.push(
+                    elements
+                        .iter()
+                        .map(|e| match e {
+                            ContextElement::Mrkdwn { text, .. } => self.convert_text(text),
+                            ContextElement::PlainText { text, .. } => text.clone(),
+                            ContextElement::Image { alt_text, .. } => format!("[{}]", alt_text),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                Block::Divider { .. } => lines.push("\u{2500}".repeat(20)),
+                Block::Header { text, .. } => lines.push(format!("*{}*", text.text().trim())),
+                Block::Actions { elements, .. } => lines.push(
+                    elements
+                        .iter()
+                        .filter_map(&element)
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                Block::Image {
+                    alt_text, title, ..
+                } => lines.push(format!(
+                    "[image: {}]",
+                    title.as_ref().map_or(alt_text.as_str(), |t| t.text())
+                )),
+                Block::RichText { elements, .. } => {
+                    for element in elements {
+                        self.convert_rich_text(element, 0, &mut lines);
+                    }
+                }
+                Block::Unknown => {}
+            }
+        }
+        lines.retain(|line| !line.is_empty());
+        lines.join("\n")
+    }
+
+    fn convert_rich_text(
+        &self,
+        element: &::slack::rtm::RichTextElement,
+        depth: usize,
+        lines: &mut Vec<String>,
+    ) {
+        use slack::rtm::{RichTextElement, RichTextListStyle};
+
+        match element {
+            RichTextElement::RichTextSection { elements } => {
+                lines.push(self.convert_rich_text_inline(elements));
+            }
+            RichTextElement::RichTextList {
+                style,
+                elements,
+                indent,
+                offset,
+                ..
+            } => {
+                let indent = "  ".repeat(depth + indent.unwrap_or(0) as usize);
+                for (i, item) in elements.iter().enumerate() {
+                    let mut item_lines = Vec::new();
+                    self.convert_rich_text(item, depth + 1, &mut item_lines);
+                    let marker = match style {
+                        RichTextListStyle::Bullet => String::from("\u{2022}"),
+                        RichTextListStyle::Ordered => {
+                            format!("{}.", i + 1 + offset.unwrap_or(0) as usize)
+                        }
+                    };
+                    for (l, line) in item_lines.into_iter().enumerate() {
+                        if l == 0 {
+                            lines.push(format!("{}{} {}", indent, marker, line));
+                        } else {
+                            lines.push(line);
+                        }
+                    }
+                }
+            }
+            RichTextElement::RichTextPreformatted { elements, .. } => {
+                lines.push(String::from("```"));
+                lines.extend(
+                    self.convert_rich_text_inline(elements)
+                        .lines()
+                        .map(String::from),
+                );
+                lines.push(String::from("```"));
+            }
+            RichTextElement::RichTextQuote { elements, .. } => {
+                for line in self.convert_rich_text_inline(elements).lines() {
+                    lines.push(format!("> {}", line));
+                }
+            }
+            RichTextElement::Unknown => {}
+        }
+    }
+
+    fn convert_rich_text_inline(&self, elements: &[::slack::rtm::RichTextInline]) -> String {
+        use slack::rtm::{RichTextInline, RichTextStyle};
+
+        // Put mrkdwn markers around the text, but outside any whitespace at its ends
+        fn styled(text: &str, style: Option<RichTextStyle>) -> String {
+            let style = style.unwrap_or_default();
+            let core = text.trim();
+            if core.is_empty() {
+                return text.to_string();
+            }
+            let mut marked = core.to_string();
+            if style.code {
+                marked = format!("`{}`", marked);
+            }
+            if style.bold {
+                marked = format!("*{}*", marked);
+            }
+            if style.italic {
+                marked = format!("_{}_", marked);
+            }
+            if style.strike {
+                marked = format!("~{}~", marked);
+            }
+            let start = text.find(core).unwrap_or(0);
+            format!("{}{}{}", &text[..start], marked, &text[start + core.len()..])
+        }
+
+        let mut out = String::new();
+        for element in elements {
+            match element {
+                RichTextInline::Text { text, style } => out.push_str(&styled(text, *style)),
+                RichTextInline::Link {
+                    url, text, style, ..
+                } => match text {
+                    // Keep the URL around, the text alone doesn't say where the link goes
+                    Some(text) if text.trim() != url => {
+                        out.push_str(&format!("{} ({})", styled(text, *style), url))
+                    }
+                    _ => out.push_str(&styled(url, *style)),
+                },
+                RichTextInline::User { user_id, style } => {
+                    let name = self
+                        .users
+                        .get_right(user_id)
+                        .map_or(user_id.as_str(), |n| n.as_ref());
+                    out.push_str(&styled(&format!("@{}", name), *style));
+                }
+                RichTextInline::Channel { channel_id, style } => {
+                    let name = self
+                        .channels
+                        .get_right(channel_id)
+                        .map_or(channel_id.as_str(), |n| n.as_ref());
+                    out.push_str(&styled(&format!("#{}", name), *style));
+                }
+                RichTextInline::Usergroup { usergroup_id, .. } => {
+                    out.push_str(&format!("@{}", usergroup_id))
+                }
+                RichTextInline::Emoji {
+                    name, skin_tone, ..
+                } => match skin_tone {
+                    Some(tone) => out.push_str(&format!(":{}::skin-tone-{}:", name, tone)),
+                    None => out.push_str(&format!(":{}:", name)),
+                },
+                RichTextInline::Broadcast { range, .. } => out.push_str(&format!("@{}", range)),
+                RichTextInline::Color { value, .. } => out.push_str(value),
+                RichTextInline::Date {
+                    timestamp,
+                    fallback,
+                    ..
+                } => match fallback {
+                    Some(fallback) => out.push_str(fallback),
+                    None => out.push_str(&::conn::DateTime::from(::slack::Timestamp {
+                        microseconds: timestamp * 1_000_000,
+                    }).to_string()),
+                },
+                RichTextInline::Unknown => {}
+            }
+        }
+        out
+    }
+
+    fn convert_attachment(&self, attachment: &::slack::rtm::Attachment) -> ::conn::Attachment {
+        let convert = |text: &Option<String>| text.as_ref().map(|t| self.convert_text(t));
+        let mut text = self.message_text(&attachment.text, attachment.blocks.as_ref());
+        let title = convert(&attachment.title);
+        let fields: Vec<_> = attachment
+            .fields
+            .iter()
+            .flat_map(|fields| fields.iter())
+            .map(|field| ::conn::AttachmentField {
+                title: convert(&field.title).unwrap_or_default(),
+                value: convert(&field.value).unwrap_or_default(),
+            })
+            .collect();
+        // Some integrations only fill in the fallback
+        if text.is_empty() && title.is_none() && fields.is_empty() {
+            text = convert(&attachment.fallback).unwrap_or_default();
+        }
+        ::conn::Attachment {
+            color: attachment.color.clone(),
+            pretext: convert(&attachment.pretext),
+            author: attachment
+                .author_name
+                .clone()
+                .or_else(|| attachment.service_name.clone()),
+            title,
+            text,
+            fields,
+            footer: convert(&attachment.footer),
         }
     }
 
@@ -242,21 +722,79 @@ impl Handler {
             {
                 let _ = self.tui_sender.send(Event::Message(Message {
                     channel: self.pending_messages[index].channel.clone(),
-                    contents: ack.text,
+                    contents: ack.text.into(),
                     is_mention: false,
                     reactions: Vec::new(),
                     sender: self.my_name.clone(),
                     server: self.server_name.clone(),
                     timestamp: ack.ts.into(),
+                    dialect: ::markup::Dialect::Slack,
                 }));
                 self.pending_messages.swap_remove(index);
                 return;
             }
         }
 
-        use slack::rtm;
         match ::serde_json::from_str::<::slack::rtm::Event>(&message) {
-            Ok(rtm::Event::Message {
+            Ok(event) => self.process_event(event),
+            // Don't yet support this thing
+            Err(e) => {
+                let v: ::serde_json::Value = ::serde_json::from_str(&message).unwrap();
+                error!(
+                    "Failed to parse:\n{}\n{}",
+                    ::serde_json::to_string_pretty(&v).unwrap(),
+                    e
+                );
+            }
+        }
+    }
+
+    /// Handle one envelope from a Socket Mode connection, returning the acknowledgement to send
+    pub fn process_socket_message(&mut self, message: &str) -> Option<String> {
+        use slack::socket_mode::{Ack, Envelope, EnvelopeType, EventCallback};
+        let envelope = match ::serde_json::from_str::<Envelope>(message) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                error!("Failed to parse Socket Mode envelope:\n{}\n{}", message, e);
+                return None;
+            }
+        };
+
+        // Acknowledge even the envelopes we can't make sense of, or Slack will keep retrying them
+        let ack = envelope
+            .envelope_id
+            .as_ref()
+            .and_then(|id| ::serde_json::to_string(&Ack::new(id)).ok());
+
+        match (envelope.ty, envelope.payload) {
+            (EnvelopeType::EventsApi, Some(payload)) => {
+                match ::serde_json::from_value::<EventCallback>(payload.clone()) {
+                    Ok(callback) => self.process_event(callback.event),
+                    Err(e) => error!(
+                        "Failed to parse:\n{}\n{}",
+                        ::serde_json::to_string_pretty(&payload).unwrap(),
+                        e
+                    ),
+                }
+            }
+            (EnvelopeType::Disconnect, _) => {
+                info!(
+                    "Slack is closing the Socket Mode connection to {}: {}",
+                    self.server_name,
+                    envelope.reason.unwrap_or_default()
+                );
+            }
+            _ => {}
+        }
+
+        ack
+    }
+
+    /// Translate an event from either transport into the TUI's events
+    fn process_event(&mut self, event: ::slack::rtm::Event) {
+        use slack::rtm;
+        match event {
+            rtm::Event::Message {
                 message:
                     rtm::Message::MessageChanged(rtm::MessageMessageChanged {
                         channel,
@@ -265,12 +803,32 @@ impl Handler {
                         ..
                     }),
                 ..
-            }) => {
+            } => {
                 if let (
-                    rtm::Message::Standard(rtm::MessageStandard { text, .. }),
+                    rtm::Message::Standard(rtm::MessageStandard {
+                        text,
+                        attachments,
+                        files,
+                        blocks,
+                        ..
+                    }),
                     rtm::Message::Standard(rtm::MessageStandard { ts: Some(ts), .. }),
                 ) = (*message, *previous_message)
                 {
+                    // Link unfurls show up as an edit that adds attachments
+                    let contents = ::conn::Body {
+                        text: self.message_text(&text, blocks.as_ref()),
+                        attachments: attachments
+                            .unwrap_or_default()
+                            .iter()
+                            .map(|a| self.convert_attachment(a))
+                            .collect(),
+                        files: files
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(convert_file)
+                            .collect(),
+                    };
                     let _ = self.tui_sender.send(Event::MessageEdited {
                         server: self.server_name.clone(),
                         channel: self
@@ -279,11 +837,11 @@ impl Handler {
                             .unwrap_or(&IString::from(channel.as_str()))
                             .clone(),
                         timestamp: ts.into(),
-                        contents: text,
+                        contents,
                     });
                 }
             }
-            Ok(rtm::Event::ReactionAdded { item, reaction, .. }) => {
+            rtm::Event::ReactionAdded { item, reaction, .. } => {
                 use slack::rtm::Reactable;
                 let (channel_id, timestamp) = match item {
                     Reactable::Message { channel, ts } => (channel, ts),
@@ -293,11 +851,11 @@ impl Handler {
                         server: self.server_name.clone(),
                         channel: channel.clone(),
                         timestamp: timestamp.into(),
-                        reaction: reaction.into(),
+                        reaction: self.resolve_emoji(&reaction).into(),
                     });
                 }
             }
-            Ok(rtm::Event::ReactionRemoved { item, reaction, .. }) => {
+            rtm::Event::ReactionRemoved { item, reaction, .. } => {
                 use slack::rtm::Reactable;
                 let (channel_id, timestamp) = match item {
                     Reactable::Message { channel, ts } => (channel, ts),
@@ -307,15 +865,15 @@ impl Handler {
                         server: self.server_name.clone(),
                         channel: channel.clone(),
                         timestamp: timestamp.into(),
-                        reaction: reaction.into(),
+                        reaction: self.resolve_emoji(&reaction).into(),
                     });
                 }
             }
             // Miscellaneous slack messages that should appear as normal messages
-            Ok(rtm::Event::Message {
+            rtm::Event::Message {
                 message: slack_message,
                 ..
-            }) => {
+            } => {
                 if let Some(omnimessage) = self.to_omni(slack_message.clone(), None) {
                     let _ = self.tui_sender.send(Event::Message(omnimessage));
                 } else {
@@ -324,7 +882,7 @@ impl Handler {
             }
 
             // Got some other kind of event we haven't handled yet
-            Ok(rtm::Event::ChannelMarked { channel, ts, .. }) => {
+            rtm::Event::ChannelMarked { channel, ts, .. } => {
                 let _ = self.tui_sender.send(Event::MarkChannelRead {
                     server: self.server_name.clone(),
                     channel: self
@@ -336,7 +894,7 @@ impl Handler {
                 });
             }
 
-            Ok(::slack::rtm::Event::GroupMarked { channel, ts, .. }) => {
+            rtm::Event::GroupMarked { channel, ts, .. } => {
                 let _ = self.tui_sender.send(Event::MarkChannelRead {
                     server: self.server_name.clone(),
                     channel: self
@@ -348,59 +906,509 @@ impl Handler {
                 });
             }
 
-            Ok(_) => {}
+            rtm::Event::ImMarked { channel, ts, .. } => {
+                if let Some(name) = self.channels.get_right(&channel.into()) {
+                    let _ = self.tui_sender.send(Event::MarkChannelRead {
+                        server: self.server_name.clone(),
+                        channel: name.clone(),
+                        read_at: ts.into(),
+                    });
+                }
+            }
 
-            // Don't yet support this thing
-            Err(e) => {
-                let v: ::serde_json::Value = ::serde_json::from_str(&message).unwrap();
-                error!(
-                    "Failed to parse:\n{}\n{}",
-                    ::serde_json::to_string_pretty(&v).unwrap(),
-                    e
-                );
+            rtm::Event::ChannelCreated { channel, .. } => {
+                if channel.creator == Some(self.my_id) {
+                    self.add_channel(channel.id.into(), channel.name);
+                }
+            }
+
+            rtm::Event::ChannelJoined { channel } => {
+                self.add_channel(channel.id.into(), channel.name);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn add_channel(&mut self, id: ::slack::ConversationId, name: String) {
+        if self.channels.get_right(&id).is_some() {
+            return;
+        }
+        let name = IString::from(name);
+        self.channels.insert(id, name.clone());
+        let _ = self.tui_sender.send(Event::ChannelJoined {
+            server: self.server_name.clone(),
+            channel: name,
+        });
+    }
+}
+
+/// Group DMs are named for everyone in them, so `mpdm-alice--bob--carol-1` becomes
+/// `@alice,bob,carol`
+fn mpim_name(name: &str) -> String {
+    let members = name.trim_start_matches("mpdm-");
+    let members = members.rfind('-').map_or(members, |i| &members[..i]);
+    format!("@{}", members.split("--").collect::<Vec<_>>().join(","))
+}
+
+/// Who we are connected as, and for RTM where to connect to
+struct Session {
+    my_id: ::slack::UserId,
+    my_name: String,
+    team_name: String,
+    rtm_url: Option<String>,
+}
+
+fn start_session(api: &Api, transport: Transport) -> Box<Future<Item = Session, Error = Error>> {
+    use slack::http::{auth, rtm};
+    match transport {
+        Transport::Rtm => Box::new(api.call("rtm.connect", &()).map(
+            |response: rtm::ConnectResponse| Session {
+                my_id: response.slf.id.as_str().into(),
+                my_name: response.slf.name,
+                team_name: response.team.name,
+                rtm_url: Some(response.url),
+            },
+        )),
+        // Socket Mode has nothing like rtm.connect, so ask who the token belongs to instead
+        Transport::SocketMode => Box::new(api.call("auth.test", &()).map(
+            |response: auth::TestResponse| Session {
+                my_id: response.user_id,
+                my_name: response.user,
+                team_name: response.team,
+                rtm_url: None,
+            },
+        )),
+    }
+}
+
+/// Feed events from the RTM websocket to the handler, and send it what comes in on `input`
+fn run_rtm(
+    url: &str,
+    handler: Arc<RwLock<Handler>>,
+    input: mpsc::Receiver<::websocket::OwnedMessage>,
+    handle: &Handle,
+) -> impl Future<Item = (), Error = ()> {
+    use websocket::result::WebSocketError;
+    use websocket::OwnedMessage::{Close, Ping, Pong, Text};
+    let status = Arc::clone(&handler);
+    let builder = match ::websocket::ClientBuilder::new(url) {
+        Ok(builder) => builder,
+        Err(e) => {
+            error!("Slack gave us a websocket URL that doesn't parse: {}", e);
+            status
+                .read()
+                .unwrap()
+                .send_state(ConnectionState::Disconnected);
+            return future::Either::A(future::err(()));
+        }
+    };
+    let connection = builder
+        .async_connect_secure(None, handle)
+        .and_then(move |(duplex, _)| {
+            let (sink, stream) = duplex.split();
+            stream
+                .filter_map(move |message| match message {
+                    Close(_) => {
+                        error!("websocket closed");
+                        None
+                    }
+                    Ping(m) => Some(Pong(m)),
+                    Text(text) => {
+                        handler.write().unwrap().process_slack_message(&text);
+                        None
+                    }
+                    _ => None,
+                }).select(input.map_err(|_| WebSocketError::NoDataAvailable))
+                .forward(sink)
+        }).map(|_| ())
+        .map_err(|e| error!("Slack websocket failed: {}", e))
+        .then(move |result| {
+            // RTM connections aren't reopened
+            status
+                .read()
+                .unwrap()
+                .send_state(ConnectionState::Disconnected);
+            result
+        });
+    future::Either::B(connection)
+}
+
+/// Feed events from Socket Mode to the handler, opening a new connection whenever Slack closes one
+fn run_socket_mode(
+    app: Api,
+    handler: Arc<RwLock<Handler>>,
+    handle: Handle,
+) -> impl Future<Item = (), Error = ()> {
+    use futures::future::{loop_fn, Loop};
+    use websocket::OwnedMessage::{Close, Ping, Pong, Text};
+    loop_fn((), move |()| {
+        let handler = Arc::clone(&handler);
+        let status = Arc::clone(&handler);
+        let handle = handle.clone();
+        open_socket_mode(&app)
+            .map_err(|e| {
+                error!("Failed to open a Socket Mode connection: {}", e);
+                // A token that Slack turned down won't do any better next time
+                if is_auth_error(&e) {
+                    None
+                } else {
+                    Some(Duration::from_secs(10))
+                }
+            }).and_then(move |url| {
+                let builder = match ::websocket::ClientBuilder::new(&url) {
+                    Ok(builder) => builder,
+                    Err(e) => {
+                        error!("Slack gave us a Socket Mode URL that doesn't parse: {}", e);
+                        return future::Either::A(future::err(Some(Duration::from_secs(10))));
+                    }
+                };
+                let connection = builder
+                    .async_connect_secure(None, &handle)
+                    .and_then(move |(duplex, _)| {
+                        handler
+                            .read()
+                            .unwrap()
+                            .send_state(ConnectionState::Connected);
+                        let (sink, stream) = duplex.split();
+                        stream
+                            .take_while(|message| match message {
+                                Close(_) => Ok(false),
+                                _ => Ok(true),
+                            }).filter_map(move |message| match message {
+                                Ping(m) => Some(Pong(m)),
+                                Text(text) => handler
+                                    .write()
+                                    .unwrap()
+                                    .process_socket_message(&text)
+                                    .map(Text),
+                                _ => None,
+                            }).forward(sink)
+                    }).map_err(|e| {
+                        error!("Socket Mode connection failed: {}", e);
+                        Some(Duration::from_secs(1))
+                    });
+                future::Either::B(connection)
+            }).then(move |result| {
+                use futures::future::Either;
+                // Slack closing the connection is routine, so only wait after a failure
+                let pause = match result {
+                    Ok(_) => Duration::default(),
+                    Err(Some(pause)) => pause,
+                    Err(None) => {
+                        status
+                            .read()
+                            .unwrap()
+                            .send_state(ConnectionState::Disconnected);
+                        return Either::A(future::ok(Loop::Break(())));
+                    }
+                };
+                status
+                    .read()
+                    .unwrap()
+                    .send_state(ConnectionState::Reconnecting);
+                Either::B(
+                    Delay::new(Instant::now() + pause).then(|_| Ok(Loop::<(), ()>::Continue(()))),
+                )
+            })
+    })
+}
+
+/// How many channels have their history fetched at once
+const HISTORY_WORKERS: usize = 2;
+
+/// A channel whose history is next in line
+struct Job {
+    id: ::slack::ConversationId,
+    /// Known once conversations.info has been called
+    read_at: Option<::conn::DateTime>,
+    /// Fetch the history even with nothing unread, because someone asked for it or because
+    /// everything else is done
+    fetch: bool,
+}
+
+/// Which channels still need their history, so that the ones someone is looking at or that have
+/// unreads are fetched before the rest
+#[derive(Default)]
+struct Backlog {
+    /// Channels that have been asked for, most recent first
+    wanted: VecDeque<::slack::ConversationId>,
+    /// Channels we don't know the read state of yet
+    unknown: VecDeque<::slack::ConversationId>,
+    /// Channels with nothing unread, which can wait until everything else is done
+    read: VecDeque<(::slack::ConversationId, ::conn::DateTime)>,
+    busy: HashSet<::slack::ConversationId>,
+    loaded: HashSet<::slack::ConversationId>,
+    /// How many workers are taking jobs
+    workers: usize,
+}
+
+impl Backlog {
+    /// Move a channel to the front of the line, returning whether a worker should be started
+    /// for it
+    fn want(&mut self, id: ::slack::ConversationId) -> bool {
+        if self.loaded.contains(&id) {
+            return false;
+        }
+        self.wanted.retain(|wanted| *wanted != id);
+        self.wanted.push_front(id);
+        if self.workers < HISTORY_WORKERS {
+            self.workers += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next_job(&mut self) -> Option<Job> {
+        let wanted = self
+            .wanted
+            .iter()
+            .position(|id| !self.busy.contains(id))
+            .and_then(|i| self.wanted.remove(i));
+        let job = if let Some(id) = wanted {
+            self.unknown.retain(|unknown| *unknown != id);
+            let read_at = self
+                .read
+                .iter()
+                .position(|(read, _)| *read == id)
+                .and_then(|i| self.read.remove(i))
+                .map(|(_, read_at)| read_at);
+            Some(Job {
+                id,
+                read_at,
+                fetch: true,
+            })
+        } else {
+            self.unknown
+                .pop_front()
+                .map(|id| Job {
+                    id,
+                    read_at: None,
+                    fetch: false,
+                }).or_else(|| {
+                    self.read.pop_front().map(|(id, read_at)| Job {
+                        id,
+                        read_at: Some(read_at),
+                        fetch: true,
+                    })
+                })
+        };
+        match job {
+            Some(ref job) => {
+                self.busy.insert(job.id);
             }
+            None => self.workers -= 1,
+        }
+        job
+    }
+}
+
+/// Everything a history worker needs
+#[derive(Clone)]
+struct HistoryLoader {
+    api: Api,
+    handler: Arc<RwLock<Handler>>,
+    sender: SyncSender<Event>,
+    backlog: Arc<Mutex<Backlog>>,
+}
+
+impl HistoryLoader {
+    fn send_read_state(
+        &self,
+        id: ::slack::ConversationId,
+        read_at: ::conn::DateTime,
+        unreads: Option<u32>,
+    ) {
+        let handler = self.handler.read().unwrap();
+        if let Some(name) = handler.channels.get_right(&id) {
+            let _ = self.sender.send(Event::ReadState {
+                server: handler.server_name.clone(),
+                channel: name.clone(),
+                read_at,
+                unreads: unreads.map(|n| n as usize),
+            });
         }
     }
+
+    /// Take jobs from the backlog until it's empty
+    fn run(self) -> impl Future<Item = (), Error = ()> {
+        use futures::future::{loop_fn, Either, Loop};
+        loop_fn(self, |loader| {
+            let job = loader.backlog.lock().unwrap().next_job();
+            match job {
+                Some(job) => Either::A(loader.load(job).then(|_| Ok(Loop::Continue(loader)))),
+                None => Either::B(future::ok(Loop::Break(()))),
+            }
+        })
+    }
+
+    /// Find out whether a channel has unreads, and fetch its history if it does or if the job
+    /// says to. Otherwise it goes to the back of the line.
+    fn load(&self, job: Job) -> impl Future<Item = (), Error = ()> {
+        use futures::future::Either;
+        use slack::http::conversations::{self, ConversationInfo};
+
+        let id = job.id;
+        let fetch = job.fetch;
+        let read_state = match job.read_at {
+            Some(read_at) => Either::A(future::ok((read_at, false))),
+            None => Either::B(
+                self.api
+                    .post_form(
+                        "conversations.info",
+                        ::serde_urlencoded::to_string(conversations::InfoRequest::new(id))
+                            .unwrap_or_default(),
+                    ).map(|response: conversations::InfoResponse| match response.channel {
+                        ConversationInfo::Channel {
+                            last_read,
+                            unread_count_display,
+                            ..
+                        } => (
+                            last_read.map_or_else(::conn::DateTime::now, |t| t.into()),
+                            unread_count_display,
+                        ),
+                        ConversationInfo::Group {
+                            last_read,
+                            unread_count_display,
+                            ..
+                        } => (last_read.into(), unread_count_display),
+                        ConversationInfo::OpenDirectMessage {
+                            last_read,
+                            unread_count_display,
+                            ..
+                        } => (last_read.into(), Some(unread_count_display)),
+                        ConversationInfo::ClosedDirectMessage { .. } => {
+                            (::conn::DateTime::now(), Some(0))
+                        }
+                    }).map({
+                        let loader = self.clone();
+                        move |(read_at, unreads)| {
+                            loader.send_read_state(id, read_at, unreads);
+                            (read_at, unreads.unwrap_or(0) > 0)
+                        }
+                    }),
+            ),
+        };
+
+        let loader = self.clone();
+        read_state
+            .and_then(move |(read_at, has_unreads)| {
+                if !fetch && !has_unreads {
+                    let mut backlog = loader.backlog.lock().unwrap();
+                    backlog.busy.remove(&id);
+                    backlog.read.push_back((id, read_at));
+                    return Either::A(future::ok(()));
+                }
+
+                let mut req = conversations::HistoryRequest::new(id);
+                req.limit = Some(1000);
+                let history = loader
+                    .api
+                    .post_form(
+                        "conversations.history",
+                        ::serde_urlencoded::to_string(req).unwrap_or_default(),
+                    ).map(move |response: conversations::HistoryResponse| {
+                        let handler = loader.handler.read().unwrap();
+                        let name = match handler.channels.get_right(&id) {
+                            Some(name) => name.clone(),
+                            None => return,
+                        };
+                        response
+                            .messages
+                            .into_iter()
+                            .rev()
+                            .filter_map(|m| handler.to_omni(m, Some(id)))
+                            .for_each(|m| {
+                                let _ = loader.sender.send(Event::Message(m));
+                            });
+                        let _ = loader.sender.send(Event::HistoryLoaded {
+                            server: handler.server_name.clone(),
+                            channel: name,
+                            read_at,
+                        });
+
+                        let mut backlog = loader.backlog.lock().unwrap();
+                        backlog.busy.remove(&id);
+                        backlog.wanted.retain(|wanted| *wanted != id);
+                        backlog.loaded.insert(id);
+                    });
+                Either::B(history)
+            }).or_else({
+                let backlog = Arc::clone(&self.backlog);
+                move |e| {
+                    // Leave it out of the backlog, so it's tried again if someone asks for it
+                    backlog.lock().unwrap().busy.remove(&id);
+                    error!("Failed to load history: {}", e);
+                    Ok(())
+                }
+            })
+    }
 }
 
 pub struct SlackConn {
-    token: String,
+    api: Api,
+    loader: HistoryLoader,
+    transport: Transport,
     team_name: IString,
     users: BiMap<::slack::UserId, IString>,
-    channels: BiMap<::slack::ConversationId, IString>,
     channel_names: Vec<IString>,
+    kinds: HashMap<IString, ChannelKind>,
     handler: Arc<RwLock<Handler>>,
     _sender: SyncSender<Event>,
     emoji: Vec<IString>,
 }
 
 impl SlackConn {
-    pub fn create_on(token: &str, sender: SyncSender<Event>) -> Result<(), Error> {
+    pub fn create_on(
+        token: &str,
+        transport: Transport,
+        app_token: Option<&str>,
+        sender: SyncSender<Event>,
+    ) -> Result<(), Error> {
+        let app_token = match (transport, app_token) {
+            (Transport::SocketMode, None) => {
+                return Err(Error::Other(String::from(
+                    "Socket Mode needs an app_token (xapp-...) as well as a token",
+                )))
+            }
+            (_, app_token) => app_token.map(Api::new),
+        };
+        let api = Api::new(token);
+
         // Launch all of the request
-        use slack::http::{conversations, emoji, rtm, users};
-        let emoji_recv = get_slack("emoji.list", &token, &());
-        let connect_recv = get_slack("rtm.connect", &token, &());
-        let users_recv = get_slack("users.list", &token, users::ListRequest::new());
+        use slack::http::{conversations, emoji, users};
+        let emoji_recv = api.call("emoji.list", &());
+        let session_recv = start_session(&api, transport);
+        let users_recv = api.call("users.list", users::ListRequest::new());
 
         use slack::http::conversations::ChannelType::*;
         let mut req = conversations::ListRequest::new();
         req.types = vec![PublicChannel, PrivateChannel, Mpim, Im];
-        let conversations_recv = get_slack("conversations.list", &token, req);
+        let conversations_recv = api.call("conversations.list", req);
 
         // We need to know about the users first so that we can digest the list of conversations
-        let users_response: users::ListResponse = users_recv.join()??;
+        let users_response: users::ListResponse = users_recv.wait()?;
 
         let mut users: BiMap<::slack::UserId, IString> = BiMap::new();
+        let mut bots = HashSet::new();
         for user in users_response.members {
+            if user.is_bot || user.is_app_user {
+                bots.insert(user.id);
+            }
             users.insert(user.id, IString::from(user.name));
         }
 
-        let response_channels: conversations::ListResponse = conversations_recv.join()??;
+        let response_channels: conversations::ListResponse = conversations_recv.wait()?;
 
         use slack::http::conversations::Conversation::*;
         let mut channels = BiMap::new();
         let mut channel_names: Vec<IString> = Vec::new();
-        for (id, name) in response_channels
+        let mut kinds = HashMap::new();
+        // Private channels and group DMs come with where we stopped reading, the rest have to be
+        // asked about one at a time
+        let mut read_at = Vec::new();
+        for (id, name, kind) in response_channels
             .channels
             .into_iter()
             .filter_map(|channel| match channel {
@@ -412,154 +1420,137 @@ impl SlackConn {
                     is_mpim: false,
                     is_archived: false,
                     ..
-                } => Some((id, name.into())),
+                } => Some((id, name.into(), ChannelKind::Public)),
                 Group {
                     id,
                     name,
                     is_member: true,
                     is_im: false,
-                    is_mpim: false,
+                    is_mpim,
                     is_archived: false,
+                    last_read,
                     ..
-                } => Some((id, name.into())),
-                /*
-                DirectMessage { id, user, .. } => {
-                    users.get_right(&user).map(|name| (id, name.clone()))
+                } => {
+                    let name: IString = if is_mpim {
+                        mpim_name(&name).into()
+                    } else {
+                        name.into()
+                    };
+                    read_at.push((name.clone(), last_read.into()));
+                    let kind = if is_mpim {
+                        ChannelKind::Direct
+                    } else {
+                        ChannelKind::Private
+                    };
+                    Some((id, name, kind))
                 }
-                */
+                DirectMessage {
+                    id,
+                    user,
+                    is_user_deleted: false,
+                    ..
+                } => users.get_right(&user).map(|name| {
+                    let kind = if bots.contains(&user) {
+                        ChannelKind::App
+                    } else {
+                        ChannelKind::Direct
+                    };
+                    (id, format!("@{}", name).into(), kind)
+                }),
                 _ => None,
             }) {
             let name: IString = name;
+            kinds.insert(name.clone(), kind);
             channel_names.push(name.clone());
             channels.insert(id, name);
         }
 
         channel_names.sort();
 
-        let connect_response: rtm::ConnectResponse = connect_recv.join()??;
-
-        let websocket_url = connect_response.url.clone();
+        let session = session_recv.wait()?;
 
-        let my_name = IString::from(connect_response.slf.name);
-        let team_name = IString::from(connect_response.team.name);
+        let my_name = IString::from(session.my_name);
+        let team_name = IString::from(session.team_name);
         let (input_sender, input_channel) = mpsc::channel(0);
 
         let handler = Arc::new(RwLock::new(Handler {
             channels: channels.clone(),
             users: users.clone(),
             server_name: team_name.clone(),
+            my_id: session.my_id,
             my_name: my_name.clone(),
             input_sender,
             tui_sender: sender.clone(),
             pending_messages: Vec::new(),
+            emoji_aliases: HashMap::new(),
         }));
 
         // Give the emoji handle as long as possible to complete
-        let emoji: emoji::ListResponse = emoji_recv.join()??;
+        let emoji: emoji::ListResponse = emoji_recv.wait()?;
+
+        let custom_emoji = emoji.emoji.unwrap_or_default();
+        handler.write().unwrap().emoji_aliases = custom_emoji
+            .iter()
+            .filter(|(_, value)| value.starts_with("alias:"))
+            .map(|(name, value)| (name.clone(), value["alias:".len()..].to_string()))
+            .collect();
 
-        let mut emoji = emoji
-            .emoji
-            .unwrap_or_default()
+        // Autocomplete offers the workspace's emoji alongside the standard ones
+        let mut emoji = custom_emoji
             .keys()
             .map(|e| IString::from(e.as_str()))
+            .chain(::emoji::shortcodes().iter().map(|&e| IString::from(e)))
             .collect::<Vec<_>>();
         emoji.sort();
+        emoji.dedup();
+
+        let mut backlog = Backlog::default();
+        backlog.unknown = channels.iter().map(|(id, _)| *id).collect();
+        backlog.workers = HISTORY_WORKERS;
+        let loader = HistoryLoader {
+            api: api.clone(),
+            handler: Arc::clone(&handler),
+            sender: sender.clone(),
+            backlog: Arc::new(Mutex::new(backlog)),
+        };
 
         let _ = sender.send(Event::Connected(Box::new(SlackConn {
-            token: String::from(token),
+            api: api.clone(),
+            loader: loader.clone(),
+            transport,
             users,
-            channels: channels.clone(),
             channel_names,
+            kinds,
             team_name: team_name.clone(),
             _sender: sender.clone(),
             handler: handler.clone(),
             emoji,
         })));
 
-        let thread_handler = Arc::clone(&handler);
-
-        // Spin off a thread that will feed message events back to the TUI
-        // websocket does not support the new tokio :(
-        thread::spawn(move || {
-            use websocket::result::WebSocketError;
-            use websocket::OwnedMessage::{Close, Ping, Pong, Text};
-            let mut core = ::tokio_core::reactor::Core::new().unwrap();
-            let runner = ::websocket::ClientBuilder::new(&websocket_url)
-                .unwrap()
-                .async_connect_secure(None, &core.handle())
-                .and_then(|(duplex, _)| {
-                    let (sink, stream) = duplex.split();
-                    stream
-                        .filter_map(|message| match message {
-                            Close(_) => {
-                                error!("websocket closed");
-                                None
-                            }
-                            Ping(m) => Some(Pong(m)),
-                            Text(text) => {
-                                thread_handler.write().unwrap().process_slack_message(&text);
-                                None
-                            }
-                            _ => None,
-                        }).select(input_channel.map_err(|_| WebSocketError::NoDataAvailable))
-                        .forward(sink)
-                });
-            core.run(runner).unwrap();
-        });
-
-        let mut requests = Vec::new();
-        for (conversation_id, _) in channels.clone() {
-            use slack::http::conversations;
+        for (channel, read_at) in read_at {
+            let _ = sender.send(Event::ReadState {
+                server: team_name.clone(),
+                channel,
+                read_at,
+                unreads: None,
+            });
+        }
 
-            let info_recv = get_slack(
-                "conversations.info",
-                &token,
-                conversations::InfoRequest::new(conversation_id),
-            );
-            let mut req = conversations::HistoryRequest::new(conversation_id);
-            req.limit = Some(1000);
-            let history_recv = get_slack("conversations.history", &token, req);
-
-            requests.push((info_recv, history_recv));
-        }
-
-        // Launch threads to populate the message history
-        for ((conversation_id, conversation_name), (info_recv, history_recv)) in
-            channels.clone().into_iter().zip(requests.into_iter())
-        {
-            use slack::http::conversations::ConversationInfo;
-            let server_name = team_name.clone();
-
-            if let Ok(info_response) = info_recv.join()? {
-                let info_response: conversations::InfoResponse = info_response;
-                let read_at = match info_response.channel {
-                    ConversationInfo::Channel { last_read, .. } => last_read
-                        .map(|t| t.into())
-                        .unwrap_or_else(::conn::DateTime::now),
-                    ConversationInfo::Group { last_read, .. } => last_read.into(),
-                    ConversationInfo::ClosedDirectMessage { .. } => ::conn::DateTime::now(),
-                    ConversationInfo::OpenDirectMessage { last_read, .. } => last_read.into(),
-                };
+        let event_handler = Arc::clone(&handler);
 
-                if let Ok(history_response) = history_recv.join()? {
-                    let history_response: conversations::HistoryResponse = history_response;
-                    let handler_handle = handler.read().unwrap();
-                    history_response
-                        .messages
-                        .into_iter()
-                        .rev()
-                        .filter_map(|m| handler_handle.to_omni(m, Some(conversation_id)))
-                        .for_each(|m| {
-                            let _ = sender.send(Event::Message(m));
-                        });
-                }
+        // Feed message events back to the TUI from the shared event loop
+        if let Some(websocket_url) = session.rtm_url {
+            ::runtime::spawn_local(move |handle| {
+                run_rtm(&websocket_url, event_handler, input_channel, handle)
+            });
+        } else if let Some(app) = app_token {
+            ::runtime::spawn_local(move |handle| {
+                run_socket_mode(app, event_handler, handle.clone())
+            });
+        }
 
-                let _ = sender.send(Event::HistoryLoaded {
-                    server: server_name,
-                    channel: conversation_name,
-                    read_at,
-                });
-            }
+        for _ in 0..HISTORY_WORKERS {
+            ::runtime::spawn(loader.clone().run()).forget();
         }
 
         Ok(())
@@ -575,6 +1566,13 @@ impl Conn for SlackConn {
         &self.channel_names
     }
 
+    fn channel_kind(&self, channel: &str) -> ChannelKind {
+        self.kinds
+            .get(channel)
+            .cloned()
+            .unwrap_or_else(|| ChannelKind::from_name(channel))
+    }
+
     fn send_channel_message(&mut self, channel: &str, contents: &str) {
         let mut handler_handle = self.handler.write().unwrap();
         let contents = handler_handle.to_slack(contents.to_string());
@@ -586,6 +1584,19 @@ impl Conn for SlackConn {
             }
         };
 
+        // Socket Mode can't send anything but acknowledgements, and the message comes back to us as
+        // an event just like anyone else's
+        if self.transport == Transport::SocketMode {
+            use slack::http::chat::PostMessageRequest;
+            use slack::http::SlackError;
+            let response = self.api.call::<_, SlackError>(
+                "chat.postMessage",
+                PostMessageRequest::new(channel_id, &contents),
+            );
+            report_failure(response, format!("send message to {}", channel));
+            return;
+        }
+
         let mut id = 0;
         while handler_handle.pending_messages.iter().any(|m| m.id == id) {
             id += 1;
@@ -612,11 +1623,12 @@ impl Conn for SlackConn {
             .unwrap();
     }
 
-    fn mark_read(&self, channel: &str) {
-        use slack::http::{channels, groups, im};
+    fn mark_read(&self, channel: &str, timestamp: ::conn::DateTime) {
+        use slack::http::conversations::MarkRequest;
+        use slack::http::SlackError;
 
-        let channel_or_group_id = match self.channels.get_left(channel) {
-            Some(s) => *s,
+        let id = match self.handler.read().unwrap().channels.get_left(channel) {
+            Some(id) => *id,
             None => {
                 error!(
                     "Tried to mark unread for channel {} in server {} but channel does not exist",
@@ -627,28 +1639,25 @@ impl Conn for SlackConn {
             }
         };
 
-        let token = self.token.clone();
-
-        let timestamp = ::conn::DateTime::now().into();
+        let req = MarkRequest::new(id, timestamp.into());
+        let response = self.api.call::<_, SlackError>("conversations.mark", req);
+        report_failure(response, format!("mark {} read", channel));
+    }
 
-        use slack::http::SlackError;
-        match channel_or_group_id {
-            ::slack::ConversationId::Channel(channel_id) => {
-                let req = channels::MarkRequest::new(channel_id, timestamp);
-                let _ =
-                    get_slack::<channels::MarkRequest, SlackError>("channels.mark", &token, req);
-            }
-            ::slack::ConversationId::Group(group_id) => {
-                let req = groups::MarkRequest::new(group_id, timestamp);
-                let _ = get_slack::<groups::MarkRequest, SlackError>("groups.mark", &token, req);
-            }
-            ::slack::ConversationId::DirectMessage(dm_id) => {
-                let req = im::MarkRequest::new(dm_id, timestamp);
-                let _ = get_slack::<im::MarkRequest, SlackError>("im.mark", &token, req);
-            }
+    fn load_history(&self, channel: &str) {
+        let id = match self.handler.read().unwrap().channels.get_left(channel) {
+            Some(id) => *id,
+            None => return,
+        };
+        if self.loader.backlog.lock().unwrap().want(id) {
+            ::runtime::spawn(self.loader.clone().run()).forget();
         }
     }
 
+    fn stats(&self) -> Option<String> {
+        Some(self.api.scheduler.summary())
+    }
+
     fn autocomplete(&self, word: &str) -> Vec<String> {
         match word.chars().next() {
             Some('@') => self
@@ -659,6 +1668,9 @@ impl Conn for SlackConn {
                 .map(|s| String::from("@") + s)
                 .collect(),
             Some('#') => self
+                .handler
+                .read()
+                .unwrap()
                 .channels
                 .iter()
                 .map(|(_id, name)| name)
@@ -687,10 +1699,9 @@ impl Conn for SlackConn {
     }
 
     fn add_reaction(&self, reaction: &str, channel: &str, timestamp: ::conn::DateTime) {
-        let token = self.token.clone();
         let name = IString::from(reaction);
 
-        let channel = match self.channels.get_left(channel) {
+        let channel = match self.handler.read().unwrap().channels.get_left(channel) {
             Some(c) => *c,
             None => {
                 error!(
@@ -710,14 +1721,136 @@ impl Conn for SlackConn {
             },
         );
 
-        let url = format!(
-            "https://slack.com/api/{}?token={}&{}",
-            "reactions.add",
-            token,
-            ::serde_urlencoded::to_string(req).unwrap_or_default()
-        ).parse::<::reqwest::Url>()
-        .unwrap();
+        let response = self.api.call::<_, ::slack::http::SlackError>("reactions.add", req);
+        report_failure(response, format!("add reaction {}", reaction));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(id: &str) -> ::slack::ConversationId {
+        ::slack::ChannelId::from(id).into()
+    }
+
+    #[test]
+    fn group_dm_names() {
+        assert_eq!(mpim_name("mpdm-alice--bob--carol-1"), "@alice,bob,carol");
+        assert_eq!(mpim_name("mpdm-mary-jane--bob-2"), "@mary-jane,bob");
+    }
+
+    #[test]
+    fn wanted_channels_jump_the_line() {
+        let mut backlog = Backlog::default();
+        backlog.unknown = vec![channel("C01"), channel("C02"), channel("C03")].into();
+        backlog.workers = HISTORY_WORKERS;
+
+        let first = backlog.next_job().unwrap();
+        assert!(first.id == channel("C01") && first.read_at.is_none() && !first.fetch);
+        // Nothing unread there, so it waits for everything else
+        backlog.busy.remove(&first.id);
+        backlog.read.push_back((first.id, ::conn::DateTime::now()));
+
+        // Already at the limit, so a running worker picks it up
+        assert!(!backlog.want(channel("C03")));
+        assert!(backlog.next_job().unwrap().id == channel("C03"));
+        assert!(backlog.next_job().unwrap().id == channel("C02"));
+
+        // Asking again for one that's waiting at the back reuses what we know about it
+        backlog.want(channel("C01"));
+        let again = backlog.next_job().unwrap();
+        assert!(again.id == channel("C01") && again.read_at.is_some() && again.fetch);
+
+        // A worker that runs out of jobs stops, and one starts again when there's more to do
+        assert!(backlog.next_job().is_none());
+        assert_eq!(backlog.workers, HISTORY_WORKERS - 1);
+        assert!(backlog.want(channel("C02")));
+    }
+
+    /// Answers every call with an empty conversations.history response
+    fn fake_api() -> String {
+        use std::io::{BufRead, BufReader, Read, Write};
+        let listener = ::std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        ::std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.to_lowercase().starts_with("content-length:") {
+                        length = line[15..].trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                reader.read_exact(&mut vec![0; length]).unwrap();
+                let body = r#"{"ok": true, "messages": [], "has_more": false, "pin_count": 0}"#;
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+            }
+        });
+        url
+    }
 
-        let _ = CLIENT.get(url).send();
+    #[test]
+    fn history_loads_for_channels_with_nothing_unread() {
+        let (sender, events) = ::std::sync::mpsc::sync_channel(16);
+        let mut channels = BiMap::new();
+        channels.insert(channel("C01"), IString::from("general"));
+        channels.insert(channel("C02"), IString::from("random"));
+        let handler = Handler {
+            channels,
+            users: BiMap::new(),
+            server_name: "Work".into(),
+            my_id: ::slack::UserId::from("U01"),
+            my_name: "me".into(),
+            input_sender: mpsc::channel(1).0,
+            tui_sender: sender.clone(),
+            pending_messages: Vec::new(),
+            emoji_aliases: HashMap::new(),
+        };
+        let mut api = Api::new("xoxp-test");
+        api.base_url = Arc::new(fake_api());
+
+        // Both are known to have nothing unread, and one of them has been asked for
+        let mut backlog = Backlog::default();
+        let now = ::conn::DateTime::now();
+        backlog.read = vec![(channel("C01"), now), (channel("C02"), now)].into();
+        assert!(backlog.want(channel("C02")));
+        let backlog = Arc::new(Mutex::new(backlog));
+        let loader = HistoryLoader {
+            api,
+            handler: Arc::new(RwLock::new(handler)),
+            sender,
+            backlog: Arc::clone(&backlog),
+        };
+
+        let (done, finished) = ::std::sync::mpsc::channel();
+        ::std::thread::spawn(move || {
+            let _ = done.send(::runtime::spawn(loader.run()).wait());
+        });
+        let timeout = Duration::from_secs(10);
+        assert_eq!(finished.recv_timeout(timeout), Ok(Ok(())));
+
+        let loaded = events
+            .try_iter()
+            .filter_map(|event| match event {
+                Event::HistoryLoaded { channel, .. } => Some(channel),
+                _ => None,
+            }).collect::<Vec<_>>();
+        assert_eq!(loaded, [IString::from("random"), IString::from("general")]);
+        let backlog = backlog.lock().unwrap();
+        assert!(backlog.read.is_empty() && backlog.busy.is_empty());
+        assert_eq!(backlog.loaded.len(), 2);
+        assert_eq!(backlog.workers, 0);
     }
 }
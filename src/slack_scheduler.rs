@@ -0,0 +1,241 @@
+//! Pacing Slack Web API calls to stay under each method's rate limit
+//!
+//! Slack limits every method separately, per workspace, according to which tier it's in. Callers
+//! wait for a turn, which lets a short burst through immediately and spaces the rest out evenly.
+//! A 429 holds back every call to that method until its `Retry-After` has passed.
+use futures::future::{loop_fn, Loop};
+use futures::Future;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio_timer::Delay;
+
+/// See https://api.slack.com/docs/rate-limits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tier {
+    One,
+    Two,
+    Three,
+    Four,
+    /// Methods with limits of their own, like chat.postMessage's one message per second
+    Special,
+}
+
+impl Tier {
+    fn of(method: &str) -> Tier {
+        match method {
+            "rtm.connect" | "rtm.start" | "apps.connections.open" => Tier::One,
+            "conversations.list" | "users.list" | "emoji.list" | "team.info" => Tier::Two,
+            "auth.test" | "users.info" | "oauth.v2.access" => Tier::Four,
+            "chat.postMessage" | "chat.meMessage" => Tier::Special,
+            // Most of what's left, including history, info, marks and reactions
+            _ => Tier::Three,
+        }
+    }
+
+    fn per_minute(self) -> u32 {
+        match self {
+            Tier::One => 1,
+            Tier::Two => 20,
+            Tier::Three => 50,
+            Tier::Four => 100,
+            Tier::Special => 60,
+        }
+    }
+
+    /// How many calls can go out back to back before pacing kicks in
+    fn burst(self) -> u32 {
+        match self {
+            Tier::One => 1,
+            Tier::Special => 2,
+            _ => self.per_minute() / 5,
+        }
+    }
+}
+
+/// Whether a call can safely be sent again when we can't tell if it got through. A 429 means
+/// Slack didn't act on the call, so those are always retried.
+pub fn is_idempotent(method: &str) -> bool {
+    match method {
+        "chat.postMessage" | "chat.meMessage" | "files.upload" => false,
+        _ => true,
+    }
+}
+
+#[derive(Default)]
+struct Method {
+    /// When the next call would go out if there were no burst allowance
+    next: Option<Instant>,
+    retry_after: Option<Instant>,
+    queued: usize,
+}
+
+#[derive(Default)]
+struct State {
+    methods: HashMap<&'static str, Method>,
+    in_flight: usize,
+    sent: u64,
+    rate_limited: u64,
+    retried: u64,
+}
+
+#[derive(Default)]
+pub struct Scheduler {
+    state: Mutex<State>,
+}
+
+/// A call that's been let through, which counts as in flight until it's dropped
+pub struct Turn {
+    scheduler: Arc<Scheduler>,
+}
+
+impl Drop for Turn {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.scheduler.state.lock() {
+            state.in_flight -= 1;
+        }
+    }
+}
+
+impl Scheduler {
+    /// Wait until `method` can be called without going over its limit
+    pub fn wait_turn(
+        scheduler: &Arc<Scheduler>,
+        method: &'static str,
+    ) -> impl Future<Item = Turn, Error = ::tokio_timer::Error> {
+        let scheduler = Arc::clone(scheduler);
+        scheduler.state.lock().unwrap().methods.entry(method).or_default().queued += 1;
+        loop_fn(scheduler, move |scheduler| {
+            Delay::new(scheduler.reserve(method)).map(move |()| {
+                // A 429 may have come back for this method while we were waiting
+                if scheduler.start(method) {
+                    Loop::Break(Turn { scheduler })
+                } else {
+                    Loop::Continue(scheduler)
+                }
+            })
+        })
+    }
+
+    /// Claim the next slot for `method`, returning when it opens
+    fn reserve(&self, method: &'static str) -> Instant {
+        let tier = Tier::of(method);
+        let interval = Duration::from_secs(60) / tier.per_minute();
+        let tolerance = interval * (tier.burst() - 1);
+
+        let mut state = self.state.lock().unwrap();
+        let slot = state.methods.entry(method).or_default();
+        let now = Instant::now();
+        let due = slot.next.map_or(now, |next| next.max(now));
+        let start = due
+            .checked_sub(tolerance)
+            .map_or(now, |start| start.max(now))
+            .max(slot.retry_after.unwrap_or(now));
+        slot.next = Some(due.max(start) + interval);
+        start
+    }
+
+    /// Let a call to `method` through, unless Slack has asked us to hold off
+    fn start(&self, method: &'static str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        {
+            let slot = state.methods.entry(method).or_default();
+            if slot.retry_after.map_or(false, |until| until > Instant::now()) {
+                return false;
+            }
+            slot.queued -= 1;
+        }
+        state.in_flight += 1;
+        state.sent += 1;
+        true
+    }
+
+    /// Hold back every call to `method` until Slack says we can try again
+    pub fn rate_limited(&self, method: &'static str, retry_after: Duration) {
+        let mut state = self.state.lock().unwrap();
+        state.rate_limited += 1;
+        let slot = state.methods.entry(method).or_default();
+        let until = Instant::now() + retry_after;
+        slot.retry_after = Some(slot.retry_after.map_or(until, |previous| previous.max(until)));
+    }
+
+    pub fn retrying(&self) {
+        self.state.lock().unwrap().retried += 1;
+    }
+
+    /// A one-line summary for `/stats`
+    pub fn summary(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut waiting = state
+            .methods
+            .iter()
+            .filter(|(_, m)| m.queued > 0)
+            .map(|(name, m)| (m.queued, *name))
+            .collect::<Vec<_>>();
+        waiting.sort_by(|a, b| b.cmp(a));
+        let mut summary = format!(
+            "{} queued, {} in flight, {} sent, {} rate limited, {} retried",
+            waiting.iter().map(|w| w.0).sum::<usize>(),
+            state.in_flight,
+            state.sent,
+            state.rate_limited,
+            state.retried,
+        );
+        if !waiting.is_empty() {
+            let busiest = waiting
+                .iter()
+                .take(3)
+                .map(|(queued, name)| format!("{} {}", name, queued))
+                .collect::<Vec<_>>();
+            summary.push_str(&format!(" ({})", busiest.join(", ")));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_core::reactor::Core;
+
+    fn wait_turn(core: &mut Core, scheduler: &Arc<Scheduler>, method: &'static str) -> Turn {
+        core.run(Scheduler::wait_turn(scheduler, method)).unwrap()
+    }
+
+    #[test]
+    fn bursts_then_paces() {
+        let mut core = Core::new().unwrap();
+        let scheduler = Arc::new(Scheduler::default());
+        let start = Instant::now();
+        // Tier 4 lets 20 through at once, then one every 600ms
+        for _ in 0..20 {
+            wait_turn(&mut core, &scheduler, "auth.test");
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+        wait_turn(&mut core, &scheduler, "auth.test");
+        assert!(start.elapsed() >= Duration::from_millis(500));
+    }
+
+    #[test]
+    fn retry_after_holds_back_a_method() {
+        let mut core = Core::new().unwrap();
+        let scheduler = Arc::new(Scheduler::default());
+        scheduler.rate_limited("conversations.history", Duration::from_millis(300));
+        let start = Instant::now();
+        wait_turn(&mut core, &scheduler, "users.info");
+        assert!(start.elapsed() < Duration::from_millis(100));
+        wait_turn(&mut core, &scheduler, "conversations.history");
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert!(scheduler.summary().contains("1 rate limited"));
+    }
+
+    #[test]
+    fn counts_what_is_in_flight() {
+        let mut core = Core::new().unwrap();
+        let scheduler = Arc::new(Scheduler::default());
+        let turn = wait_turn(&mut core, &scheduler, "users.list");
+        assert!(scheduler.summary().starts_with("0 queued, 1 in flight, 1 sent"));
+        drop(turn);
+        assert!(scheduler.summary().starts_with("0 queued, 0 in flight"));
+    }
+}
@@ -0,0 +1,93 @@
+//! Files the client keeps between runs, stored as JSON in `$XDG_DATA_HOME/omnichat`
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub fn state_dir() -> Option<PathBuf> {
+    let dir = ::dirs::data_local_dir()?.join("omnichat");
+    if let Err(e) = fs::create_dir_all(&dir) {
+        error!("Couldn't create state directory {:?}: {}", dir, e);
+        return None;
+    }
+    Some(dir)
+}
+
+pub fn load<T: DeserializeOwned>(name: &str) -> Option<T> {
+    read(&state_dir()?.join(name))
+}
+
+fn read<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let contents = fs::read_to_string(path).ok()?;
+    match ::serde_json::from_str(&contents) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("Ignoring unreadable state file {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+pub fn save<T: Serialize>(name: &str, value: &T) {
+    if let Some(dir) = state_dir() {
+        write(&dir.join(name), value);
+    }
+}
+
+/// Write through a temporary file so a crash mid-write doesn't lose the old contents
+fn write<T: Serialize>(path: &Path, value: &T) {
+    let temp_path = path.with_extension("tmp");
+    let result = ::serde_json::to_string(value)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| {
+            ::secret::private()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .open(&temp_path)
+                .and_then(|mut file| file.write_all(contents.as_bytes()))
+                .map_err(|e| e.to_string())
+        })
+        .and_then(|_| fs::rename(&temp_path, path).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        error!("Couldn't save {:?}: {}", path, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn temp_file(name: &str) -> PathBuf {
+        let dir = ::std::env::temp_dir().join(format!("omnichat-state-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_file("round_trip.json");
+        let mut drafts = HashMap::new();
+        drafts.insert("general".to_string(), "half a thought".to_string());
+        write(&path, &drafts);
+        assert_eq!(read::<HashMap<String, String>>(&path), Some(drafts));
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn missing_file() {
+        let path = temp_file("missing.json");
+        assert_eq!(read::<Vec<String>>(&path), None);
+    }
+
+    #[test]
+    fn corrupt_file() {
+        let path = temp_file("corrupt.json");
+        fs::write(&path, "{\"general\": ").unwrap();
+        assert_eq!(read::<HashMap<String, String>>(&path), None);
+    }
+}
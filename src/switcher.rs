@@ -0,0 +1,116 @@
+//! Ctrl-K quick switching between channels on every server
+use conn::DateTime;
+
+/// Something the switcher can jump to
+pub struct Entry {
+    pub server: usize,
+    pub channel: usize,
+    /// What's matched against and drawn, like "#general  Work"
+    pub label: String,
+    /// 2 for unread mentions or DMs, 1 for other unreads, 0 for nothing new
+    pub unread: u8,
+    pub last_activity: Option<DateTime>,
+}
+
+/// State of an open quick switcher
+#[derive(Default)]
+pub struct Switcher {
+    pub query: String,
+    /// Which of the ranked entries is highlighted
+    pub selected: usize,
+}
+
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace() || "#@/-_.,".contains(c)
+}
+
+/// How well `query` matches `candidate` as a subsequence ignoring case, or `None` if it doesn't.
+/// Runs of consecutive characters and matches at the start of words count for the most.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    let query = query.to_lowercase().chars().collect::<Vec<_>>();
+    let candidate = candidate.to_lowercase().chars().collect::<Vec<_>>();
+    let first = match query.first() {
+        Some(first) => first,
+        None => return Some(0),
+    };
+
+    // Matching greedily from the first place that works can miss a much better match later on,
+    // like the start of a word, so try every place the first character appears
+    (0..candidate.len())
+        .filter(|&start| candidate[start] == *first)
+        .filter_map(|start| {
+            let mut score = 0;
+            let mut previous: Option<usize> = None;
+            let mut position = start;
+            for q in &query {
+                let found = position + candidate[position..].iter().position(|c| c == q)?;
+                score += 1;
+                if found == 0 || is_boundary(candidate[found - 1]) {
+                    score += 8;
+                }
+                match previous {
+                    Some(previous) if previous + 1 == found => score += 5,
+                    Some(previous) => score -= (found - previous - 1).min(5) as i64,
+                    None => {}
+                }
+                previous = Some(found);
+                position = found + 1;
+            }
+            // Between otherwise equal matches, prefer the shorter name
+            Some(score * 4 - candidate.len() as i64 / 8)
+        }).max()
+}
+
+/// Drop entries that don't match `query` and put the rest in order: best match first, then
+/// unreads, then whatever was active most recently
+pub fn rank(query: &str, entries: Vec<Entry>) -> Vec<Entry> {
+    let mut ranked = entries
+        .into_iter()
+        .filter_map(|entry| score(query, &entry.label).map(|score| (score, entry)))
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(score_a, a), (score_b, b)| {
+        score_b
+            .cmp(score_a)
+            .then(b.unread.cmp(&a.unread))
+            .then(b.last_activity.cmp(&a.last_activity))
+    });
+    ranked.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(label: &str, unread: u8) -> Entry {
+        Entry {
+            server: 0,
+            channel: 0,
+            label: String::from(label),
+            unread,
+            last_activity: None,
+        }
+    }
+
+    #[test]
+    fn matches_subsequences_ignoring_case() {
+        assert!(score("gnrl", "#General").is_some());
+        assert!(score("", "#general").is_some());
+        assert!(score("lg", "#general").is_none());
+    }
+
+    #[test]
+    fn word_starts_beat_scattered_letters() {
+        assert!(score("rd", "#random-dev").unwrap() > score("rd", "#hardware").unwrap());
+        assert!(score("gen", "#general").unwrap() > score("gen", "#oxygen-tanks").unwrap());
+    }
+
+    #[test]
+    fn unreads_break_ties() {
+        let ranked = rank(
+            "ops",
+            vec![entry("#ops  Work", 0), entry("#ops  Home", 1), entry("#docs", 2)],
+        );
+        let labels = ranked.iter().map(|e| e.label.as_str()).collect::<Vec<_>>();
+        assert_eq!(labels, ["#ops  Home", "#ops  Work"]);
+    }
+}
@@ -1,10 +1,45 @@
 use chan_message::ChanMessage;
-use conn::{Conn, DateTime, Event, IString, Message};
+use conn::{ChannelKind, Conn, ConnectionState, DateTime, Event, IString, Message};
 use cursor_vec::CursorVec;
+use history::History;
+use panes::{Direction, Rect};
+use sidebar::{Row, Section, Sidebar};
 use std::cmp::{max, min};
-use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, SyncSender};
+use vi::ViEditor;
+
+const MIN_SIDEBAR_WIDTH: u16 = 8;
+const SERVER_SEPARATOR: &str = " • ";
+
+/// Asks the terminal to report when it gains or loses focus, for as long as this is alive
+struct FocusReporting;
+
+impl FocusReporting {
+    fn enable() -> Self {
+        use std::io::Write;
+        let mut out = ::std::io::stdout();
+        let _ = write!(out, "\x1b[?1004h");
+        let _ = out.flush();
+        FocusReporting
+    }
+}
 
-const CHAN_WIDTH: u16 = 20;
+impl Drop for FocusReporting {
+    fn drop(&mut self) {
+        use std::io::Write;
+        let mut out = ::std::io::stdout();
+        let _ = write!(out, "\x1b[?1004l");
+        let _ = out.flush();
+    }
+}
+
+/// Where keyboard input goes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Input,
+    Messages,
+}
 
 pub struct Tui {
     servers: CursorVec<Server>,
@@ -13,20 +48,100 @@ pub struct Tui {
     events: Receiver<Event>,
     sender: SyncSender<Event>,
     server_scroll_offset: usize,
+    /// Wrap the server bar onto more rows when it's too long, instead of scrolling it
+    wrap_server_bar: bool,
+    /// Where each server's name was drawn in the server bar, as the row, the columns it covers and
+    /// the server's index
+    server_bar_hits: Vec<(u16, ::std::ops::Range<u16>, usize)>,
     autocompletions: Vec<String>,
     autocomplete_index: usize,
     cursor_pos: usize,
     _guards: (
         ::termion::screen::AlternateScreen<::std::io::Stdout>,
         ::termion::raw::RawTerminal<::std::io::Stdout>,
+        FocusReporting,
+        ::termion::input::MouseTerminal<::std::io::Stdout>,
     ),
+    /// Whether the terminal has focus. Terminals that don't report focus are always focused.
+    terminal_focused: bool,
+    /// A mention arrived that the user may not have seen, so ring the bell on the next draw
+    ring_bell: bool,
     previous_terminal_height: u16,
     truncate_buffer_to: usize,
+    vi: Option<ViEditor>,
+    focus: Focus,
+    /// Which message is picked out while the messages are focused, counting back from the newest
+    selected_message: usize,
+    /// Scroll the selected message into view on the next draw, once it has moved or been used
+    reveal_selection: bool,
+    /// The newest message from the backend that the focused pane showed when it was last drawn
+    newest_drawn: Option<DateTime>,
+    history: History,
+    /// How far back into the current channel's history Up has gone
+    history_position: Option<usize>,
+    history_search: Option<::history::Search>,
+    switcher: Option<::switcher::Switcher>,
+    /// Drafts for servers that haven't connected yet or channels they don't list, so saving
+    /// doesn't drop them
+    pending_drafts: Drafts,
+    /// What's on disk, to skip rewriting the file when nothing has changed
+    saved_drafts: Drafts,
+    buffer_numbers: BufferNumbers,
+    session: Session,
+    muted_channels: Vec<String>,
+    starred_channels: Vec<String>,
+    sidebar: Sidebar,
+    /// While catching up, the channels that have been visited, by server and channel name
+    catch_up: Option<HashSet<(IString, IString)>>,
+}
+
+/// Unsent input lines by server then channel name
+type Drafts = BTreeMap<String, BTreeMap<String, String>>;
+
+/// Numbers for the channels each server had when it last connected, by server then channel name
+type BufferNumbers = BTreeMap<String, BTreeMap<String, usize>>;
+
+/// What a pane is showing. The focused pane always shows the current channel, so its entry is
+/// only brought up to date when focus moves or the session is saved.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct PaneView {
+    server: String,
+    channel: String,
+    scroll: usize,
+}
+
+/// How the message area is split up, kept between runs
+#[derive(Default, Serialize, Deserialize)]
+struct Session {
+    layout: ::panes::Layout,
+    views: BTreeMap<usize, PaneView>,
+    focused: usize,
 }
 
+impl Session {
+    fn load() -> Self {
+        let mut session: Session = ::state::load(SESSION_FILE).unwrap_or_default();
+        session.layout.clamp_ratios();
+        let panes = session.layout.panes();
+        let complete = panes.iter().all(|id| session.views.contains_key(id));
+        if panes.contains(&session.focused) && (complete || panes.len() == 1) {
+            session
+        } else {
+            error!("Ignoring a saved layout that doesn't add up");
+            Session::default()
+        }
+    }
+}
+
+const DRAFTS_FILE: &str = "drafts.json";
+const BUFFERS_FILE: &str = "buffers.json";
+const SESSION_FILE: &str = "session.json";
+const DRAFT_SAVE_INTERVAL: ::std::time::Duration = ::std::time::Duration::from_secs(30);
+
 struct Server {
     channels: Vec<Channel>,
     connection: Box<Conn>,
+    state: ConnectionState,
     name: IString,
     current_channel: usize,
     channel_scroll_offset: usize,
@@ -41,23 +156,204 @@ impl Server {
 struct Channel {
     messages: Vec<ChanMessage>,
     name: IString,
+    /// Stays the same across runs, for jumping straight to a channel on the current server with
+    /// Alt-1 or `/buffer 1`
+    number: usize,
+    kind: ChannelKind,
     read_at: DateTime,
     message_scroll_offset: usize,
     message_buffer: String,
+    /// Whether history from before we connected has arrived, or there is none to wait for
+    loaded: bool,
+    /// Unreads the backend told us about, which count until the history they're in is loaded
+    unloaded_unreads: usize,
+    /// Marked unread on purpose, so leaving it shouldn't mark it read again
+    keep_unread: bool,
+    /// The newest message that was on screen while the terminal had focus
+    seen_at: Option<DateTime>,
+}
+
+/// How much a channel in the hotlist wants attention, least first
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Activity {
+    Message,
+    Mention,
+    Direct,
+}
+
+/// Whether a `server/channel` or bare channel name in `list` picks out this channel
+fn listed(list: &[String], server: &str, channel: &str) -> bool {
+    list.iter()
+        .any(|m| *m == channel || *m == format!("{}/{}", server, channel))
+}
+
+/// The number a channel has always had on its server, or the next free one there if it's new.
+/// Each server counts from 1, so Alt-1 to Alt-9 reach the first channels of whichever server is
+/// current.
+fn buffer_number(numbers: &mut BufferNumbers, server: &str, channel: &str) -> usize {
+    let channels = numbers.entry(server.to_string()).or_default();
+    if let Some(&number) = channels.get(channel) {
+        return number;
+    }
+    let number = channels.values().max().map_or(1, |n| n + 1);
+    channels.insert(channel.to_string(), number);
+    number
+}
+
+/// Draw a channel's messages from the bottom of `area` up, after skipping `offset` lines.
+/// `selected` picks out one message, counting back from the newest. Returns the time of the
+/// newest message from the backend that made it on screen.
+fn draw_messages(
+    render_buffer: &mut String,
+    channel: &mut Channel,
+    area: Rect,
+    offset: usize,
+    selected: Option<usize>,
+) -> Option<DateTime> {
+    use std::fmt::Write;
+    use termion::color::Fg;
+    use termion::cursor::Goto;
+    use termion::{color, style};
+
+    let width = area.width as usize;
+    let num_unreads = channel.num_unreads();
+    let mut draw_unread_marker = num_unreads > 0;
+
+    // Rows are drawn bottom up, stopping at the one above the area
+    let top = area.y - 1;
+    let mut row = area.y + area.height - 1;
+    let mut skipped = 0;
+    let mut newest_drawn = None;
+    'outer: for (m, message) in channel.messages.iter_mut().rev().enumerate() {
+        // Unread marker
+        if (draw_unread_marker) && (m == num_unreads) {
+            let _ = write!(render_buffer, "{}{}", Goto(area.x, row), Fg(color::Red));
+            render_buffer.extend(::std::iter::repeat('-').take(width));
+            let _ = write!(render_buffer, "{}", Fg(color::Reset));
+            row -= 1;
+            draw_unread_marker = false;
+            if row == top {
+                break 'outer;
+            }
+        }
+
+        let backend_timestamp = if message.is_local() {
+            None
+        } else {
+            Some(*message.timestamp())
+        };
+        for line in message.formatted_to(width).lines().rev() {
+            if skipped < offset {
+                skipped += 1;
+                continue;
+            }
+            newest_drawn = newest_drawn.or(backend_timestamp);
+            let _ = write!(render_buffer, "{}", Goto(area.x, row));
+            if selected == Some(m) {
+                let _ = write!(render_buffer, "{}{}{}", style::Invert, line, style::NoInvert);
+            } else {
+                render_buffer.push_str(line);
+            }
+            row -= 1;
+            if row == top {
+                break 'outer;
+            }
+        }
+    }
+
+    // Older messages may still be on their way
+    if !channel.loaded && row > top {
+        let _ = write!(
+            render_buffer,
+            "{}{}History not loaded yet{}",
+            Goto(area.x, row),
+            Fg(color::AnsiValue::grayscale(12)),
+            Fg(color::Reset)
+        );
+        row -= 1;
+    }
+
+    // If we didn't draw the unread marker, put it at the top of the area
+    if draw_unread_marker {
+        let _ = write!(render_buffer, "{}", Goto(area.x, max(area.y, row)));
+        let _ = write!(render_buffer, "{}", Fg(color::Red));
+        render_buffer.extend(::std::iter::repeat('-').take(width));
+        let _ = write!(render_buffer, "{}", Fg(color::Reset));
+    }
+    newest_drawn
 }
 
 impl Channel {
-    fn num_unreads(&self) -> usize {
+    fn unread_messages(&self) -> impl Iterator<Item = &ChanMessage> {
+        let read_at = self.read_at;
         self.messages
             .iter()
             .rev()
-            .take_while(|m| *m.timestamp() > self.read_at)
-            .count()
+            .take_while(move |m| *m.timestamp() > read_at)
+            .filter(|m| !m.is_local())
+    }
+
+    fn num_unreads(&self) -> usize {
+        let unloaded = if self.loaded { 0 } else { self.unloaded_unreads };
+        self.unread_messages().count() + unloaded
+    }
+
+    fn num_mentions(&self) -> usize {
+        self.unread_messages().filter(|m| m.is_mention()).count()
+    }
+
+    /// Move `read_at` back so the message `selected` back from the newest and everything after
+    /// it is unread again, returning where the backend should be told it's read up to
+    fn mark_unread_from(&mut self, selected: usize) -> Option<DateTime> {
+        let index = self.messages.len().checked_sub(selected + 1)?;
+        // Client notices have made-up timestamps, so only go by the backend's messages
+        let read_at = match self.messages[..index].iter().rev().find(|m| !m.is_local()) {
+            Some(previous) => *previous.timestamp(),
+            None => {
+                let first = self.messages[index..].iter().find(|m| !m.is_local())?;
+                (*first.timestamp().as_chrono() - ::chrono::Duration::microseconds(1)).into()
+            }
+        };
+        self.read_at = read_at;
+        self.keep_unread = true;
+        Some(read_at)
+    }
+
+    /// Scroll just far enough that the message `selected` back from the newest is on screen, in
+    /// an area `width` columns wide and `height` rows tall
+    fn scroll_to_message(&mut self, selected: usize, width: usize, height: usize) {
+        let num_unreads = self.num_unreads();
+        let mut newer = self.messages.iter_mut().rev();
+        let below = newer
+            .by_ref()
+            .take(selected)
+            .map(|m| m.formatted_to(width).lines().count())
+            .sum::<usize>();
+        let lines = match newer.next() {
+            Some(message) => message.formatted_to(width).lines().count(),
+            None => return,
+        };
+        // The unread marker takes up a row when it's under the selected message
+        let marker = if num_unreads > 0 && selected >= num_unreads {
+            1
+        } else {
+            0
+        };
+        if self.message_scroll_offset > below {
+            self.message_scroll_offset = below;
+        } else if below + lines + marker > self.message_scroll_offset + height {
+            self.message_scroll_offset = below + lines + marker - height;
+        }
     }
 }
 
 impl Tui {
-    pub fn new() -> Self {
+    /// The caller makes the event channel, so the logger can use it before any state is loaded
+    pub fn new(
+        config: &::ClientConfig,
+        sender: SyncSender<Event>,
+        events: Receiver<Event>,
+    ) -> Self {
         use std::thread;
         use termion::input::TermRead;
         use termion::raw::IntoRawMode;
@@ -67,8 +363,6 @@ impl Tui {
             .into_raw_mode()
             .expect("Couldn't put the terminal in raw mode");
 
-        let (sender, reciever) = sync_channel(100);
-
         // Must be called before any threads are launched
         let winch_send = sender.clone();
         let signals = ::signal_hook::iterator::Signals::new(&[::libc::SIGWINCH])
@@ -88,6 +382,9 @@ impl Tui {
             }
         });
 
+        let drafts: Drafts = ::state::load(DRAFTS_FILE).unwrap_or_default();
+        let mut buffer_numbers: BufferNumbers = ::state::load(BUFFERS_FILE).unwrap_or_default();
+
         Self {
             servers: CursorVec::new(Server {
                 channels: vec!["Errors", "Mentions"]
@@ -95,27 +392,70 @@ impl Tui {
                     .map(|name| Channel {
                         messages: Vec::new(),
                         name: (*name).into(),
+                        number: buffer_number(&mut buffer_numbers, "Client", name),
+                        kind: ChannelKind::Public,
                         read_at: ::chrono::Utc::now().into(),
                         message_scroll_offset: 0,
                         message_buffer: String::new(),
+                        loaded: true,
+                        unloaded_unreads: 0,
+                        keep_unread: false,
+                        seen_at: None,
                     })
                     .collect(),
                 connection: ClientConn::create_on(sender.clone()),
+                state: ConnectionState::Connected,
                 channel_scroll_offset: 0,
                 current_channel: 0,
                 name: IString::from("Client"),
             }),
             longest_channel_name: 0,
             shutdown: false,
-            events: reciever,
+            events,
             sender,
             server_scroll_offset: 0,
+            wrap_server_bar: config.wrap_server_bar,
+            server_bar_hits: Vec::new(),
             autocompletions: Vec::new(),
             autocomplete_index: 0,
             cursor_pos: 0,
-            _guards: (screenguard, rawguard),
+            _guards: (
+                screenguard,
+                rawguard,
+                FocusReporting::enable(),
+                ::termion::input::MouseTerminal::from(::std::io::stdout()),
+            ),
+            terminal_focused: true,
+            ring_bell: false,
             truncate_buffer_to: 0,
             previous_terminal_height: 0,
+            vi: if config.vi_mode {
+                Some(ViEditor::new())
+            } else {
+                None
+            },
+            focus: Focus::Input,
+            selected_message: 0,
+            reveal_selection: false,
+            newest_drawn: None,
+            history: History::load(config.history_size),
+            history_position: None,
+            history_search: None,
+            switcher: None,
+            pending_drafts: drafts.clone(),
+            saved_drafts: drafts,
+            buffer_numbers,
+            session: Session::load(),
+            muted_channels: config.muted_channels.clone(),
+            starred_channels: config.starred_channels.clone(),
+            sidebar: Sidebar {
+                sections: config.sidebar_sections.clone(),
+                sort: config.sidebar_sort,
+                hide_inactive: config.hide_inactive_channels,
+                collapsed: HashSet::new(),
+                width: max(config.sidebar_width, MIN_SIDEBAR_WIDTH),
+            },
+            catch_up: None,
         }
     }
 
@@ -133,26 +473,73 @@ impl Tui {
         &mut server.channels[server.current_channel]
     }
 
+    /// Remember how far into the current channel the user has seen, if they're looking at it.
+    /// This goes by what was just drawn, so scrolling up doesn't count newer messages as seen.
+    fn note_seen(&mut self) {
+        if !self.terminal_focused {
+            return;
+        }
+        let newest = self.newest_drawn;
+        let channel = self.current_channel_mut();
+        channel.seen_at = max(channel.seen_at, newest);
+    }
+
+    /// Mark the current channel read, up to the newest message seen with the terminal focused.
+    /// Unreads we know about but haven't loaded stay unread, since they haven't been seen.
     fn reset_current_unreads(&mut self) {
         let server = self.servers.get_mut();
-        if server.channels[server.current_channel].num_unreads() > 0 {
-            server.channels[server.current_channel].read_at = ::chrono::Utc::now().into();
-            let current_channel = &server.channels[server.current_channel];
+        let channel = &mut server.channels[server.current_channel];
+        if channel.keep_unread {
+            channel.keep_unread = false;
+            return;
+        }
+        if !channel.loaded && channel.unloaded_unreads > 0 {
+            return;
+        }
+        let seen_at = match channel.seen_at {
+            Some(seen_at) => seen_at,
+            None => return,
+        };
+        if seen_at > channel.read_at {
+            channel.read_at = seen_at;
+            server.connection.mark_read(&channel.name, seen_at);
+        }
+    }
+
+    /// Move `read_at` back so the selected message and everything after it is unread again, here
+    /// and for the backend
+    fn mark_unread_from_selected(&mut self) {
+        let server = self.servers.get_mut();
+        let channel = &mut server.channels[server.current_channel];
+        if let Some(read_at) = channel.mark_unread_from(self.selected_message) {
+            server.connection.mark_read(&channel.name, read_at);
+        }
+    }
 
-            server.connection.mark_read(&current_channel.name);
+    fn channel_changed(&mut self) {
+        self.selected_message = 0;
+        if !self.current_channel().loaded {
+            let server = self.servers.get();
+            let channel = &server.channels[server.current_channel];
+            server.connection.load_history(&channel.name);
+        }
+        self.history_position = None;
+        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        if let Some(ref mut vi) = self.vi {
+            vi.buffer_changed();
         }
     }
 
     fn next_server(&mut self) {
         self.reset_current_unreads();
         self.servers.next();
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        self.channel_changed();
     }
 
     fn previous_server(&mut self) {
         self.reset_current_unreads();
         self.servers.prev();
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        self.channel_changed();
     }
 
     fn next_channel_unread(&mut self) {
@@ -170,7 +557,7 @@ impl Tui {
                 self.servers.get_mut().current_channel = index;
             }
         }
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        self.channel_changed();
     }
 
     fn previous_channel_unread(&mut self) {
@@ -190,34 +577,413 @@ impl Tui {
                 self.servers.get_mut().current_channel = index;
             }
         }
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        self.channel_changed();
     }
 
-    fn next_channel(&mut self) {
+    fn is_muted(&self, server: &str, channel: &str) -> bool {
+        listed(&self.muted_channels, server, channel)
+    }
+
+    /// The current server's sidebar, top to bottom
+    fn sidebar_rows(&self) -> Vec<Row> {
+        let server = self.servers.get();
+        let items = server
+            .channels
+            .iter()
+            .map(|c| ::sidebar::Item {
+                name: &c.name,
+                section: if listed(&self.starred_channels, &server.name, &c.name) {
+                    Section::Starred
+                } else {
+                    Section::of(c.kind)
+                },
+                unreads: c.num_unreads(),
+                last_activity: c.messages.last().map(|m| *m.timestamp()),
+                active: !c.messages.is_empty()
+                    || c.num_unreads() > 0
+                    || !c.message_buffer.is_empty(),
+            })
+            .collect::<Vec<_>>();
+        self.sidebar.rows(&items, server.current_channel)
+    }
+
+    /// Step through the current server's channels in the order the sidebar shows them
+    fn step_channel(&mut self, forward: bool) {
         self.reset_current_unreads();
+        let order = self
+            .sidebar_rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                Row::Channel(c) => Some(c),
+                Row::Header { .. } => None,
+            })
+            .collect::<Vec<_>>();
         // NLL HACK
         {
             let server = self.servers.get_mut();
-            server.current_channel += 1;
-            if server.current_channel >= server.channels.len() {
-                server.current_channel = 0;
+            let position = order
+                .iter()
+                .position(|&c| c == server.current_channel)
+                .unwrap_or(0);
+            server.current_channel = if forward {
+                order[(position + 1) % order.len()]
+            } else {
+                order[(position + order.len() - 1) % order.len()]
+            };
+        }
+        self.channel_changed();
+    }
+
+    fn set_sidebar_width(&mut self, width: u16) {
+        let (terminal_width, _) = ::termion::terminal_size().unwrap_or((80, 24));
+        self.sidebar.width = max(MIN_SIDEBAR_WIDTH, min(width, terminal_width / 2));
+        // The separator is drawn with the screen clearing, which otherwise only happens on resize
+        self.previous_terminal_height = 0;
+    }
+
+    /// Lower is read sooner: DMs and mentions, then everything else, then muted channels
+    fn unread_priority(&self, server: &Server, channel: &Channel) -> u8 {
+        if self.is_muted(&server.name, &channel.name) {
+            2
+        } else if channel.name.starts_with('@') || channel.num_mentions() > 0 {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Every other channel with unreads as server and channel indices, in the order to read them.
+    /// While catching up, channels that have already been visited are left out.
+    fn unread_channels(&self) -> Vec<(usize, usize)> {
+        let current = (self.servers.tell(), self.servers.get().current_channel);
+        let num_servers = self.servers.len();
+        let mut unread = Vec::new();
+        for (s, server) in self.servers.iter().enumerate() {
+            for (c, channel) in server.channels.iter().enumerate() {
+                let visited = self.catch_up.as_ref().map_or(false, |visited| {
+                    visited.contains(&(server.name.clone(), channel.name.clone()))
+                });
+                if (s, c) == current || visited || channel.num_unreads() == 0 {
+                    continue;
+                }
+                // Within a priority, go in order from the current channel, wrapping around
+                let mut distance = (s + num_servers - current.0) % num_servers;
+                if distance == 0 && c < current.1 {
+                    distance = num_servers;
+                }
+                unread.push(((self.unread_priority(server, channel), distance, c), (s, c)));
             }
         }
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+        unread.sort();
+        unread.into_iter().map(|(_, position)| position).collect()
     }
 
-    fn previous_channel(&mut self) {
+    /// Switch to a channel on any server, by server and channel index
+    fn go_to(&mut self, server: usize, channel: usize) {
         self.reset_current_unreads();
-        // NLL HACK
-        {
-            let server = &mut self.servers.get_mut();
-            if server.current_channel > 0 {
-                server.current_channel -= 1;
-            } else {
-                server.current_channel = server.channels.len() - 1;
+        self.servers.set(server);
+        self.servers.get_mut().current_channel = channel;
+        self.channel_changed();
+    }
+
+    /// Server and channel indices of a channel, by name
+    fn find_channel(&self, server: &str, channel: &str) -> Option<(usize, usize)> {
+        self.servers.iter().enumerate().find_map(|(s, srv)| {
+            if srv.name != server {
+                return None;
+            }
+            let c = srv.channels.iter().position(|c| c.name == channel)?;
+            Some((s, c))
+        })
+    }
+
+    /// What the focused pane is showing
+    fn current_view(&self) -> PaneView {
+        let server = self.servers.get();
+        let channel = &server.channels[server.current_channel];
+        PaneView {
+            server: server.name.to_string(),
+            channel: channel.name.to_string(),
+            scroll: channel.message_scroll_offset,
+        }
+    }
+
+    fn save_session(&mut self) {
+        let view = self.current_view();
+        self.session.views.insert(self.session.focused, view);
+        ::state::save(SESSION_FILE, &self.session);
+    }
+
+    /// Make pane `id` the focused one and switch to whatever it shows
+    fn show_pane(&mut self, id: usize) {
+        self.session.focused = id;
+        let view = match self.session.views.get(&id) {
+            Some(view) => view.clone(),
+            None => return,
+        };
+        if let Some((server, channel)) = self.find_channel(&view.server, &view.channel) {
+            self.go_to(server, channel);
+            self.current_channel_mut().message_scroll_offset = view.scroll;
+        }
+    }
+
+    /// Split the focused pane, moving focus to the new half which starts out on the same channel
+    fn split_pane(&mut self, direction: Direction) {
+        let view = self.current_view();
+        let new = self.session.layout.panes().iter().max().map_or(0, |id| id + 1);
+        self.session.views.insert(self.session.focused, view.clone());
+        self.session.views.insert(new, view);
+        self.session.layout.split(self.session.focused, direction, new);
+        self.session.focused = new;
+        self.save_session();
+    }
+
+    /// Close the focused pane, unless it's the only one
+    fn close_pane(&mut self) {
+        let panes = self.session.layout.panes();
+        let closing = self.session.focused;
+        if !self.session.layout.close(closing) {
+            return;
+        }
+        self.session.views.remove(&closing);
+        let index = panes.iter().position(|&id| id == closing).unwrap_or(0);
+        self.show_pane(panes[if index == 0 { 1 } else { index - 1 }]);
+        self.save_session();
+    }
+
+    /// Move focus to the next pane, or the previous one if `forward` is false
+    fn cycle_pane(&mut self, forward: bool) {
+        let panes = self.session.layout.panes();
+        if panes.len() < 2 {
+            return;
+        }
+        let index = panes
+            .iter()
+            .position(|&id| id == self.session.focused)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % panes.len()
+        } else {
+            (index + panes.len() - 1) % panes.len()
+        };
+        let view = self.current_view();
+        self.session.views.insert(self.session.focused, view);
+        self.show_pane(panes[next]);
+        self.save_session();
+    }
+
+    fn resize_pane(&mut self, delta: i16) {
+        if self.session.layout.resize(self.session.focused, delta) {
+            self.save_session();
+        }
+    }
+
+    /// Switch to the current server's channel with buffer number `number`, returning whether
+    /// there is one
+    fn go_to_buffer(&mut self, number: usize) -> bool {
+        let server = self.servers.tell();
+        match self.servers.get().channels.iter().position(|c| c.number == number) {
+            Some(channel) => {
+                self.go_to(server, channel);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Every other channel with unreads, by server and buffer number, with its unread count. The
+    /// label is the channel's number and name on the current server, or `server/channel` on
+    /// others, since numbers only pick out channels on the current server.
+    fn hotlist(&self) -> Vec<(usize, usize, String, usize, Activity)> {
+        let current = (self.servers.tell(), self.servers.get().current_channel);
+        let mut hotlist = Vec::new();
+        for (s, server) in self.servers.iter().enumerate() {
+            for (c, channel) in server.channels.iter().enumerate() {
+                let unreads = channel.num_unreads();
+                if (s, c) == current || unreads == 0 {
+                    continue;
+                }
+                let activity = if channel.name.starts_with('@') {
+                    Activity::Direct
+                } else if channel.num_mentions() > 0 {
+                    Activity::Mention
+                } else {
+                    Activity::Message
+                };
+                let label = if s == current.0 {
+                    format!("{}:{}", channel.number, channel.name)
+                } else {
+                    format!("{}/{}", server.name, channel.name)
+                };
+                hotlist.push((s, channel.number, label, unreads, activity));
+            }
+        }
+        hotlist.sort();
+        hotlist
+    }
+
+    /// Every channel on every server, ranked for the quick switcher's query
+    fn switcher_entries(&self, query: &str) -> Vec<::switcher::Entry> {
+        let mut entries = Vec::new();
+        for (s, server) in self.servers.iter().enumerate() {
+            for (c, channel) in server.channels.iter().enumerate() {
+                let unread = if channel.num_unreads() == 0 {
+                    0
+                } else if self.unread_priority(server, channel) == 0 {
+                    2
+                } else {
+                    1
+                };
+                entries.push(::switcher::Entry {
+                    server: s,
+                    channel: c,
+                    label: format!("{}  {}", channel.name, server.name),
+                    unread,
+                    last_activity: channel.messages.last().map(|m| *m.timestamp()),
+                });
+            }
+        }
+        ::switcher::rank(query, entries)
+    }
+
+    /// Keys for the Ctrl-K quick switcher, returning false if it isn't open
+    fn handle_switcher(&mut self, key: ::termion::event::Key) -> bool {
+        use termion::event::Key::*;
+        let mut switcher = match self.switcher.take() {
+            Some(switcher) => switcher,
+            None => return false,
+        };
+        match key {
+            Esc | Ctrl('g') | Ctrl('k') => return true,
+            Char('\n') => {
+                let chosen = self
+                    .switcher_entries(&switcher.query)
+                    .get(switcher.selected)
+                    .map(|e| (e.server, e.channel));
+                if let Some((server, channel)) = chosen {
+                    self.go_to(server, channel);
+                }
+                return true;
+            }
+            Up | Ctrl('p') => {
+                switcher.selected = switcher.selected.saturating_sub(1);
+            }
+            Down | Ctrl('n') | Char('\t') => {
+                let last = self.switcher_entries(&switcher.query).len().saturating_sub(1);
+                switcher.selected = min(switcher.selected + 1, last);
+            }
+            Backspace => {
+                switcher.query.pop();
+                switcher.selected = 0;
+            }
+            Char(c) => {
+                switcher.query.push(c);
+                switcher.selected = 0;
+            }
+            _ => {}
+        }
+        self.switcher = Some(switcher);
+        true
+    }
+
+    /// Go to the channel most worth reading next, on any server
+    fn next_unread(&mut self) {
+        if let Some(ref mut visited) = self.catch_up {
+            let server = self.servers.get();
+            let channel = &server.channels[server.current_channel];
+            visited.insert((server.name.clone(), channel.name.clone()));
+        }
+        match self.unread_channels().first() {
+            Some(&(server, channel)) => self.go_to(server, channel),
+            None => {
+                if self.catch_up.take().is_some() {
+                    self.add_local_message(String::from("All caught up"));
+                }
             }
         }
-        self.cursor_pos = min(self.cursor_pos, self.current_channel().message_buffer.len());
+    }
+
+    fn next_channel(&mut self) {
+        self.step_channel(true);
+    }
+
+    fn previous_channel(&mut self) {
+        self.step_channel(false);
+    }
+
+    /// Up and Down step through what was sent to this channel, but only from an empty input line
+    /// so that they don't clobber something half-written
+    fn recall_history(&mut self, older: bool) {
+        if self.history_position.is_none() && !self.current_channel().message_buffer.is_empty() {
+            return;
+        }
+        let position = match (self.history_position, older) {
+            (None, true) => Some(0),
+            (None, false) => return,
+            (Some(p), true) => Some(p + 1),
+            (Some(0), false) => None,
+            (Some(p), false) => Some(p - 1),
+        };
+        let text = match position {
+            Some(p) => {
+                let server = self.servers.get();
+                let channel = &server.channels[server.current_channel];
+                match self.history.channel_entries(&server.name, &channel.name).nth(p) {
+                    Some(text) => text.to_string(),
+                    None => return, // Already at the oldest entry
+                }
+            }
+            None => String::new(),
+        };
+        self.history_position = position;
+        self.current_channel_mut().message_buffer = text;
+        self.cursor_pos = self.current_channel().message_buffer.len();
+    }
+
+    /// Keys typed during a Ctrl-R search, returns false if the key should get the usual handling
+    fn handle_history_search(&mut self, key: ::termion::event::Key) -> bool {
+        use termion::event::Key::*;
+        let mut search = match self.history_search.take() {
+            Some(search) => search,
+            None => return false,
+        };
+        match key {
+            Ctrl('r') => {
+                if self
+                    .history
+                    .search(&search.query, search.skip + 1)
+                    .is_some()
+                {
+                    search.skip += 1;
+                }
+            }
+            Ctrl('g') | Esc => {
+                self.current_channel_mut().message_buffer = search.original;
+                self.cursor_pos = self.current_channel().message_buffer.len();
+                return true;
+            }
+            Backspace => {
+                search.query.pop();
+                search.skip = 0;
+            }
+            Char(c) if c != '\n' && c != '\t' => {
+                search.query.push(c);
+                search.skip = 0;
+            }
+            _ => {
+                // Accept the match into the input line, Enter stops there so it can be edited
+                let found = self
+                    .history
+                    .search(&search.query, search.skip)
+                    .map(String::from)
+                    .unwrap_or(search.original);
+                self.current_channel_mut().message_buffer = found;
+                self.cursor_pos = self.current_channel().message_buffer.len();
+                return key == Char('\n');
+            }
+        }
+        self.history_search = Some(search);
+        true
     }
 
     // Take by value because we need to own the allocation
@@ -227,39 +993,63 @@ impl Tui {
             .push(ChanMessage::from(::conn::Message {
                 server: "Client".into(),
                 channel: "Errors".into(),
-                contents: message,
+                contents: message.into(),
                 is_mention: false,
                 timestamp: ::chrono::Utc::now().into(),
                 sender: "Client".into(),
                 reactions: Vec::new(),
+                dialect: ::markup::Dialect::Plain,
             }));
     }
 
     pub fn add_server(&mut self, connection: Box<Conn>) {
+        let server_name = connection.name().to_string();
         let mut channels = connection.channels().to_vec();
         channels.sort();
-
+        let mut drafts = self
+            .pending_drafts
+            .remove(connection.name())
+            .unwrap_or_default();
+
+        // Channels the server doesn't list any more give up their numbers
+        if let Some(numbers) = self.buffer_numbers.get_mut(&server_name) {
+            numbers.retain(|name, _| channels.iter().any(|c| &**c == name.as_str()));
+        }
+        let numbers = &mut self.buffer_numbers;
         self.servers.push(Server {
             channels: channels
                 .into_iter()
                 .map(|name| Channel {
                     messages: Vec::new(),
-                    name,
-                    read_at: ::chrono::Utc::now().into(), // This is a Bad Idea; we've marked everything as read by default, when we have no right to but I'm not sure what else to use as a default
+                    number: buffer_number(numbers, connection.name(), &name),
+                    kind: connection.channel_kind(&name),
+                    // Until the backend says otherwise, only what arrives from now on is unread
+                    read_at: ::chrono::Utc::now().into(),
                     message_scroll_offset: 0,
-                    message_buffer: String::new(),
+                    message_buffer: drafts.remove(name.as_ref()).unwrap_or_default(),
+                    loaded: false,
+                    unloaded_unreads: 0,
+                    keep_unread: false,
+                    seen_at: None,
+                    name,
                 })
                 .collect(),
             name: connection.name().into(),
             connection,
+            state: ConnectionState::Connected,
             current_channel: 0,
             channel_scroll_offset: 0,
         });
+        // Hang on to drafts for channels the server doesn't list, in case they come back
+        if !drafts.is_empty() {
+            self.pending_drafts.insert(server_name.clone(), drafts);
+        }
+        ::state::save(BUFFERS_FILE, &self.buffer_numbers);
 
         self.longest_channel_name = self
             .servers
             .iter()
-            .flat_map(|s| s.channels.iter().map(|c| c.name.len()))
+            .flat_map(|s| s.channels.iter().map(|c| ::layout::width(&c.name)))
             .max()
             .unwrap_or(0) as u16
             + 1;
@@ -269,9 +1059,67 @@ impl Tui {
         while self.servers.get().name != previous_server_name {
             self.servers.next();
         }
+
+        // Pick up where the focused pane was last time, if nothing else has been picked since
+        let focused = self.session.focused;
+        let returning = self
+            .session
+            .views
+            .get(&focused)
+            .map_or(false, |view| view.server == server_name);
+        if previous_server_name == "Client" && returning {
+            self.show_pane(focused);
+        }
+    }
+
+    fn add_channel(&mut self, server_name: &str, name: IString) {
+        let server = match self.servers.iter_mut().find(|s| s.name == server_name) {
+            Some(server) => server,
+            None => {
+                error!("Can't add channel {}, no server named {}", name, server_name);
+                return;
+            }
+        };
+        if server.channels.iter().any(|c| c.name == name) {
+            return;
+        }
+
+        let width = ::layout::width(&name) as u16 + 1;
+        let current = server.channels.get(server.current_channel).map(|c| c.name.clone());
+        let kind = server.connection.channel_kind(&name);
+        let draft = match self.pending_drafts.get_mut(server_name) {
+            Some(drafts) => drafts.remove(name.as_ref()).unwrap_or_default(),
+            None => String::new(),
+        };
+        if self.pending_drafts.get(server_name).map_or(false, |d| d.is_empty()) {
+            self.pending_drafts.remove(server_name);
+        }
+        server.channels.push(Channel {
+            messages: Vec::new(),
+            number: buffer_number(&mut self.buffer_numbers, server_name, &name),
+            kind,
+            read_at: ::chrono::Utc::now().into(),
+            message_scroll_offset: 0,
+            message_buffer: draft,
+            loaded: false,
+            unloaded_unreads: 0,
+            keep_unread: false,
+            seen_at: None,
+            name,
+        });
+        server.channels.sort_by(|a, b| a.name.cmp(&b.name));
+        // Stay on the same channel even though it may have moved down the list
+        if let Some(i) = current.and_then(|n| server.channels.iter().position(|c| c.name == n)) {
+            server.current_channel = i;
+        }
+        self.longest_channel_name = self.longest_channel_name.max(width);
+        ::state::save(BUFFERS_FILE, &self.buffer_numbers);
     }
 
     fn add_message(&mut self, message: Message) {
+        if message.is_mention && !self.terminal_focused {
+            self.ring_bell = true;
+        }
         if message.is_mention {
             self.servers.get_first_mut().channels[1]
                 .messages
@@ -302,6 +1150,15 @@ impl Tui {
             }
         };
 
+        // History that loads late can overlap with messages that arrived while it was waiting
+        if channel
+            .messages
+            .binary_search_by(|m| m.timestamp().cmp(&message.timestamp))
+            .is_ok()
+        {
+            return;
+        }
+
         let needs_sort = channel
             .messages
             .last()
@@ -318,14 +1175,139 @@ impl Tui {
         }
     }
 
-    fn send_message(&mut self) {
-        let contents = self.current_channel().message_buffer.clone();
+    /// Handle commands meant for the client rather than a chat service, returning whether `input`
+    /// was one
+    fn run_command(&mut self, input: &str) -> bool {
+        let mut words = input.split_whitespace();
+        match words.next() {
+            Some("/stats") => {
+                let mut lines = self
+                    .servers
+                    .iter()
+                    .filter_map(|s| {
+                        s.connection
+                            .stats()
+                            .map(|stats| format!("{}: {}", s.name, stats))
+                    })
+                    .collect::<Vec<_>>();
+                if lines.is_empty() {
+                    lines.push(String::from("No connections have stats to report"));
+                }
+                self.add_local_message(lines.join("\n"));
+                true
+            }
+            Some("/buffer") => {
+                match words.next().map(str::parse::<usize>) {
+                    Some(Ok(number)) => {
+                        if !self.go_to_buffer(number) {
+                            self.add_local_message(format!("No buffer numbered {}", number));
+                        }
+                    }
+                    Some(Err(_)) => {
+                        self.add_local_message(String::from("Usage: /buffer [number]"));
+                    }
+                    None => {
+                        let server = self.servers.get();
+                        let mut channels = server
+                            .channels
+                            .iter()
+                            .map(|c| (c.number, c.name.clone()))
+                            .collect::<Vec<_>>();
+                        channels.sort();
+                        let lines = channels
+                            .iter()
+                            .map(|(number, name)| format!("{:>4} {}", number, name))
+                            .collect::<Vec<_>>();
+                        self.add_local_message(lines.join("\n"));
+                    }
+                }
+                true
+            }
+            Some("/sidebar") => {
+                let usage = "Usage: /sidebar sort alphabetical|activity, /sidebar hide|show, \
+                             /sidebar toggle <section> or /sidebar width <columns>";
+                match (words.next(), words.next()) {
+                    (Some("sort"), Some("alphabetical")) => {
+                        self.sidebar.sort = ::sidebar::Sort::Alphabetical;
+                    }
+                    (Some("sort"), Some("activity")) => {
+                        self.sidebar.sort = ::sidebar::Sort::Activity;
+                    }
+                    (Some("hide"), None) => self.sidebar.hide_inactive = true,
+                    (Some("show"), None) => self.sidebar.hide_inactive = false,
+                    (Some("toggle"), Some(name)) => match Section::parse(name) {
+                        Some(section) => {
+                            if !self.sidebar.collapsed.remove(&section) {
+                                self.sidebar.collapsed.insert(section);
+                            }
+                        }
+                        None => self.add_local_message(format!(
+                            "No section named {}, try starred, channels, private, dms or apps",
+                            name
+                        )),
+                    },
+                    (Some("width"), Some(width)) => match width.parse() {
+                        Ok(width) => self.set_sidebar_width(width),
+                        Err(_) => self.add_local_message(String::from(usage)),
+                    },
+                    _ => self.add_local_message(String::from(usage)),
+                }
+                true
+            }
+            Some("/catchup") => {
+                if words.next() == Some("stop") {
+                    self.catch_up = None;
+                    self.add_local_message(String::from("Stopped catching up"));
+                    return true;
+                }
+                self.catch_up = Some(HashSet::new());
+                let remaining = self.unread_channels().len();
+                if remaining == 0 {
+                    self.catch_up = None;
+                    self.add_local_message(String::from("Nothing to catch up on"));
+                } else {
+                    self.add_local_message(format!(
+                        "Catching up on {} channels, Ctrl-N goes to the next one",
+                        remaining
+                    ));
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Show something from the client in the current channel, without sending it anywhere
+    fn add_local_message(&mut self, text: String) {
+        let server = self.servers.get().name.clone();
+        let channel = self.current_channel().name.clone();
+        let message = ::conn::Message {
+            server,
+            channel,
+            contents: text.into(),
+            is_mention: false,
+            timestamp: ::chrono::Utc::now().into(),
+            sender: "Client".into(),
+            reactions: Vec::new(),
+            dialect: ::markup::Dialect::Plain,
+        };
+        self.current_channel_mut()
+            .messages
+            .push(ChanMessage::local(message));
+    }
+
+    fn send_message(&mut self, contents: String) {
         let current_channel_name = self.current_channel().name.clone();
+        if contents.starts_with('/') && self.run_command(&contents) {
+            return;
+        }
         if contents.starts_with("+:") {
             if let Some(ts) = self
                 .current_channel()
                 .messages
-                .last()
+                .iter()
+                .rev()
+                .find(|m| !m.is_local())
                 .map(|m| *m.timestamp())
             {
                 let reaction = &contents[2..contents.len() - 1];
@@ -355,13 +1337,14 @@ impl Tui {
 
         let (terminal_width, terminal_height) =
             ::termion::terminal_size().expect("TUI draw couldn't get terminal dimensions");
+        let sidebar_width = self.sidebar.width;
 
         if terminal_height != self.previous_terminal_height {
             render_buffer.clear();
             let _ = write!(render_buffer, "{}", ::termion::clear::All);
 
             for i in 1..terminal_height + 1 {
-                let _ = write!(render_buffer, "{}|", Goto(CHAN_WIDTH, i));
+                let _ = write!(render_buffer, "{}|", Goto(sidebar_width, i));
             }
             self.truncate_buffer_to = render_buffer.len();
             self.previous_terminal_height = terminal_height;
@@ -369,184 +1352,307 @@ impl Tui {
             render_buffer.truncate(self.truncate_buffer_to);
         }
 
-        let remaining_width = (terminal_width - CHAN_WIDTH) as usize;
+        let remaining_width = terminal_width.saturating_sub(sidebar_width) as usize;
         /*
         for message in &mut self.current_channel_mut().messages {
             message.format(remaining_width);
-        }
-        */
-
-        // Draw the message input area
-        // We need this message area height to render the channel messages
-        // More NLL hacking
-        let total_chars = self.current_channel().message_buffer.chars().count();
-        let rows = (total_chars / remaining_width) + 1;
-        for row in (0..rows).rev() {
-            let _ = write!(
-                render_buffer,
-                "{}",
-                Goto(CHAN_WIDTH + 1, terminal_height - (rows - row - 1) as u16)
-            );
-            render_buffer.extend(
-                self.current_channel()
-                    .message_buffer
-                    .chars()
-                    .skip(remaining_width * row)
-                    .take(remaining_width),
-            );
-        }
-        let message_area_height = terminal_height - rows as u16 + 1;
-
-        // Draw all the messages by looping over them in reverse
-        let num_unreads = self.current_channel().num_unreads();
-        let mut draw_unread_marker = num_unreads > 0;
-
-        let offset = self.current_channel().message_scroll_offset;
+        }
+        */
 
-        let mut row = message_area_height - 1;
-        let mut skipped = 0;
-        'outer: for (m, message) in self
-            .current_channel_mut()
-            .messages
-            .iter_mut()
-            .rev()
-            .enumerate()
-        {
-            // Unread marker
-            if (draw_unread_marker) && (m == num_unreads) {
+        // Draw the message input area
+        // We need this message area height to render the channel messages
+        // More NLL hacking
+        // A Ctrl-R search takes over the input line to show the query and what it found
+        let search_prompt = self.history_search.as_ref().map(|search| {
+            let prefix = format!("(reverse-i-search)`{}': ", search.query);
+            let cursor = prefix.len() - "': ".len();
+            let found = self
+                .history
+                .search(&search.query, search.skip)
+                .unwrap_or("");
+            (prefix + found, cursor)
+        });
+        // Search prompt or the input itself, and the cursor as a byte offset into it
+        let (input, cursor_byte) = match search_prompt {
+            Some((ref prompt, cursor)) => (prompt.as_str(), cursor),
+            None => {
+                let buffer = self.current_channel().message_buffer.as_str();
+                (buffer, self.cursor_pos)
+            }
+        };
+        let (cursor_row, cursor_column) = ::layout::position(input, cursor_byte, remaining_width);
+        let rows = ::layout::position(input, input.len(), remaining_width).0 + 1;
+        let selection = self
+            .vi
+            .as_ref()
+            .filter(|_| search_prompt.is_none())
+            .and_then(|vi| vi.selection(input, self.cursor_pos));
+        let mut previous_row = None;
+        for (i, g, row, column) in ::layout::place(input, remaining_width) {
+            if previous_row != Some(row) {
                 let _ = write!(
                     render_buffer,
-                    "{}{}",
-                    Goto(CHAN_WIDTH + 1, row),
-                    Fg(color::Red)
+                    "{}",
+                    Goto(
+                        sidebar_width + 1 + column as u16,
+                        terminal_height - (rows - row - 1) as u16
+                    )
                 );
-                render_buffer.extend(::std::iter::repeat('-').take(remaining_width));
-                let _ = write!(render_buffer, "{}", Fg(color::Reset));
-                row -= 1;
-                draw_unread_marker = false;
-                if row == 1 {
-                    break 'outer;
-                }
+                previous_row = Some(row);
             }
-
-            for line in message.formatted_to(remaining_width).lines().rev() {
-                if skipped < offset {
-                    skipped += 1;
-                    continue;
-                }
-                let _ = write!(render_buffer, "{}", Goto(CHAN_WIDTH + 1, row));
-                render_buffer.push_str(line);
-                row -= 1;
-                if row == 1 {
-                    break 'outer;
-                }
+            if selection.map_or(false, |(start, end)| i >= start && i < end) {
+                let _ = write!(render_buffer, "{}{}{}", style::Invert, g, style::NoInvert);
+            } else {
+                render_buffer.push_str(g);
             }
         }
+        let message_area_height = terminal_height - rows as u16 + 1;
 
-        // If we didn't draw the unread marker, put it at the top of the screen
-        if draw_unread_marker {
-            let _ = write!(render_buffer, "{}", Goto(CHAN_WIDTH + 1, max(2, row)));
-            let _ = write!(render_buffer, "{}", Fg(color::Red));
-            render_buffer.extend(::std::iter::repeat('-').take(remaining_width));
-            let _ = write!(render_buffer, "{}", Fg(color::Reset));
-        }
+        // The server names go across the top, and the hotlist under them when there is one
+        let server_rows = self.draw_server_bar(render_buffer, remaining_width);
+        let top = if self.draw_hotlist(render_buffer, server_rows + 1, remaining_width) {
+            server_rows + 1
+        } else {
+            server_rows
+        };
 
-        // Draw all the server names across the top
-        let _ = write!(render_buffer, "{}", Goto(CHAN_WIDTH + 1, 1)); // Move to the top-right corner
-        let num_servers = self.servers.len();
-        for (s, server) in self
-            .servers
-            .iter()
-            .enumerate()
-            .skip(self.server_scroll_offset)
-        {
-            if s == self.servers.tell() {
-                let _ = write!(
-                    render_buffer,
-                    "{}{}{}",
-                    style::Bold,
-                    server.name,
-                    style::Reset
-                );
-            } else if server.has_unreads() {
-                let _ = write!(
-                    render_buffer,
-                    "{}{}{}",
-                    Fg(color::Red),
-                    server.name,
-                    Fg(color::Reset),
-                );
+        // Draw each pane's messages, with a title over each one if there's more than one
+        let area = Rect {
+            x: sidebar_width + 1,
+            y: top + 1,
+            width: remaining_width as u16,
+            height: (message_area_height - 1).saturating_sub(top),
+        };
+        for separator in self.session.layout.separators(area) {
+            for row in separator.y..separator.y + separator.height {
+                let _ = write!(render_buffer, "{}|", Goto(separator.x, row));
+            }
+        }
+        let split = self.session.layout.panes().len() > 1;
+        let selected = match self.focus {
+            Focus::Messages => Some(self.selected_message),
+            Focus::Input => None,
+        };
+        let reveal_selection = self.reveal_selection;
+        self.reveal_selection = false;
+        self.newest_drawn = None;
+        for (id, mut pane) in self.session.layout.place(area) {
+            let focused = id == self.session.focused;
+            let view = if focused {
+                self.current_view()
             } else {
-                let _ = write!(
-                    render_buffer,
-                    "{}{}{}",
-                    Fg(color::AnsiValue::rgb(3, 3, 3)),
-                    server.name,
-                    Fg(color::Reset),
-                );
+                match self.session.views.get(&id) {
+                    Some(view) => view.clone(),
+                    None => continue,
+                }
+            };
+            if split && pane.height > 0 {
+                let title = format!(" {}  {}", view.channel, view.server);
+                let title = ::layout::truncate(&title, pane.width as usize);
+                let _ = write!(render_buffer, "{}", Goto(pane.x, pane.y));
+                if focused {
+                    let _ = write!(render_buffer, "{}{}", style::Invert, title);
+                    render_buffer.extend(
+                        ::std::iter::repeat(' ')
+                            .take((pane.width as usize).saturating_sub(::layout::width(&title))),
+                    );
+                    let _ = write!(render_buffer, "{}", style::NoInvert);
+                } else {
+                    let _ = write!(
+                        render_buffer,
+                        "{}{}{}",
+                        Fg(color::AnsiValue::grayscale(12)),
+                        title,
+                        Fg(color::Reset)
+                    );
+                }
+                pane.y += 1;
+                pane.height -= 1;
+            }
+            if pane.height == 0 || pane.width == 0 {
+                continue;
+            }
+            match self.find_channel(&view.server, &view.channel) {
+                Some((s, c)) => {
+                    let server = self.servers.iter_mut().nth(s).unwrap();
+                    let channel = &mut server.channels[c];
+                    let selected = if focused { selected } else { None };
+                    let mut scroll = view.scroll;
+                    // Only the focused pane has a selection, and it shows the current channel
+                    if let (Some(selected), true) = (selected, reveal_selection) {
+                        let (width, height) = (pane.width as usize, pane.height as usize);
+                        channel.scroll_to_message(selected, width, height);
+                        scroll = channel.message_scroll_offset;
+                    }
+                    let newest_drawn =
+                        draw_messages(render_buffer, channel, pane, scroll, selected);
+                    if focused {
+                        self.newest_drawn = newest_drawn;
+                    }
+                }
+                None => {
+                    let _ = write!(
+                        render_buffer,
+                        "{}{}Waiting for {} to connect{}",
+                        Goto(pane.x, pane.y + pane.height - 1),
+                        Fg(color::AnsiValue::grayscale(12)),
+                        view.server,
+                        Fg(color::Reset)
+                    );
+                }
             }
+        }
+
+        // The bottom of the sidebar shows how much is left to catch up on, or whether the messages
+        // have the keyboard, or with vi mode on, which mode we're in
+        let status = if self.catch_up.is_some() {
+            Some(format!("Catch-up: {} left", self.unread_channels().len()))
+        } else if self.focus == Focus::Messages {
+            Some(String::from("-- SCROLL --"))
+        } else {
+            self.vi.as_ref().map(|vi| String::from(vi.mode().indicator()))
+        };
+        let sidebar_height = terminal_height - status.as_ref().map_or(0, |_| 1);
+        if let Some(status) = status {
             let _ = write!(
                 render_buffer,
-                "{}",
-                if s == num_servers - 1 { "" } else { " • " }
+                "{}{}{}{}",
+                Goto(1, terminal_height),
+                Fg(color::AnsiValue::grayscale(12)),
+                status,
+                Fg(color::Reset)
             );
         }
 
         {
             // Draw all the channels for the current server down the left side
+            let rows = self.sidebar_rows();
             let server = self.servers.get_mut();
             {
-                let height = terminal_height as usize;
-                if server.current_channel + 1 > height + server.channel_scroll_offset {
-                    server.channel_scroll_offset = server.current_channel - height + 1
-                } else if server.current_channel < server.channel_scroll_offset {
-                    server.channel_scroll_offset = server.current_channel;
+                let height = sidebar_height as usize;
+                let current = rows
+                    .iter()
+                    .position(|&row| row == Row::Channel(server.current_channel))
+                    .unwrap_or(0);
+                // Show the header above the first channel rather than scrolling it off
+                let current = if current == 1 { 0 } else { current };
+                if current + 1 > height + server.channel_scroll_offset {
+                    server.channel_scroll_offset = current - height + 1
+                } else if current < server.channel_scroll_offset {
+                    server.channel_scroll_offset = current;
                 }
             }
 
             fn write_shortened_name(f: &mut String, name: &str, max_len: usize) {
-                if name.chars().count() < max_len {
-                    let _ = write!(f, "{}", name);
-                } else {
-                    f.extend(name.chars().take(max_len - 4).chain("...".chars()));
-                }
+                f.push_str(&::layout::truncate(name, max_len - 1));
             }
 
-            for (c, channel) in server
-                .channels
-                .iter_mut()
+            for (r, &entry) in rows
+                .iter()
                 .enumerate()
                 .skip(server.channel_scroll_offset)
-                .take(terminal_height as usize)
+                .take(sidebar_height as usize)
             {
+                let row = (r - server.channel_scroll_offset) as u16 + 1;
+                let c = match entry {
+                    Row::Channel(c) => c,
+                    Row::Header {
+                        section,
+                        collapsed,
+                        unreads,
+                    } => {
+                        let title = format!(
+                            "{} {}",
+                            if collapsed { '▸' } else { '▾' },
+                            section.title()
+                        );
+                        let mut count = if unreads > 0 {
+                            unreads.to_string()
+                        } else {
+                            String::new()
+                        };
+                        // Leave the count out rather than cover the title completely
+                        if count.len() + 3 > sidebar_width as usize {
+                            count.clear();
+                        }
+                        let title_width = (sidebar_width as usize)
+                            .saturating_sub(count.len() + 1)
+                            .max(2);
+                        let _ = write!(
+                            render_buffer,
+                            "{}{}{}",
+                            Goto(1, row),
+                            style::Bold,
+                            Fg(color::AnsiValue::grayscale(12))
+                        );
+                        write_shortened_name(render_buffer, &title, title_width);
+                        let _ = write!(
+                            render_buffer,
+                            "{}{}{}{}",
+                            Goto(sidebar_width.saturating_sub(count.len() as u16), row),
+                            Fg(color::Red),
+                            count,
+                            style::Reset
+                        );
+                        continue;
+                    }
+                };
+                let channel = &server.channels[c];
+                let unreads = channel.num_unreads();
+                // Leave room to mark channels with something left unsent in their input line
+                let has_draft = !channel.message_buffer.is_empty();
+                // Counts go on the right, with mentions set apart since they matter more. When
+                // the sidebar is too narrow, the mentions go first and then the unreads, so that
+                // some of the name is always left.
+                let room = (sidebar_width as usize).saturating_sub(2 + has_draft as usize);
+                let counts = if unreads > 0 && c != server.current_channel {
+                    let unreads = unreads.to_string();
+                    let mentions = match channel.num_mentions() {
+                        0 => String::new(),
+                        mentions => format!(" @{}", mentions),
+                    };
+                    if unreads.len() + mentions.len() < room {
+                        Some((unreads, mentions))
+                    } else if unreads.len() < room {
+                        Some((unreads, String::new()))
+                    } else {
+                        None
+                    }
+                } else {
+                    None
+                };
+                let counts_width = counts.as_ref().map_or(0, |(u, m)| u.len() + m.len() + 1);
+                let name_width = (sidebar_width as usize)
+                    .saturating_sub(counts_width + has_draft as usize)
+                    .max(2);
                 if c == server.current_channel {
-                    let _ = write!(
-                        render_buffer,
-                        "{}{}",
-                        Goto(1, (c - server.channel_scroll_offset) as u16 + 1),
-                        style::Bold
-                    );
-                    write_shortened_name(render_buffer, &channel.name, CHAN_WIDTH as usize);
+                    let _ = write!(render_buffer, "{}{}", Goto(1, row), style::Bold);
+                    write_shortened_name(render_buffer, &channel.name, name_width);
                     let _ = write!(render_buffer, "{}", style::Reset);
-                } else if channel.num_unreads() > 0 {
-                    let _ = write!(
-                        render_buffer,
-                        "{}{}",
-                        Goto(1, (c - server.channel_scroll_offset) as u16 + 1),
-                        Fg(color::Red)
-                    );
-                    write_shortened_name(render_buffer, &channel.name, CHAN_WIDTH as usize);
+                } else if unreads > 0 {
+                    let _ = write!(render_buffer, "{}{}", Goto(1, row), Fg(color::Red));
+                    write_shortened_name(render_buffer, &channel.name, name_width);
                     let _ = write!(render_buffer, "{}", style::Reset);
                 } else {
                     let gray = color::AnsiValue::rgb(3, 3, 3);
+                    let _ = write!(render_buffer, "{}{}", Goto(1, row), Fg(gray));
+                    write_shortened_name(render_buffer, &channel.name, name_width);
+                    let _ = write!(render_buffer, "{}", style::Reset);
+                }
+                if has_draft {
+                    render_buffer.push('✎');
+                }
+                if let Some((unreads, mentions)) = counts {
                     let _ = write!(
                         render_buffer,
-                        "{}{}",
-                        Goto(1, (c - server.channel_scroll_offset) as u16 + 1),
-                        Fg(gray)
+                        "{}{}{}{}{}{}",
+                        Goto(sidebar_width.saturating_sub(counts_width as u16), row),
+                        Fg(color::Red),
+                        unreads,
+                        Fg(color::Yellow),
+                        mentions,
+                        Fg(color::Reset)
                     );
-                    write_shortened_name(render_buffer, &channel.name, CHAN_WIDTH as usize);
-                    let _ = write!(render_buffer, "{}", style::Reset);
                 }
             }
         }
@@ -555,11 +1661,17 @@ impl Tui {
             render_buffer,
             "{}",
             Goto(
-                CHAN_WIDTH + 1 + (self.cursor_pos % remaining_width) as u16,
-                terminal_height - (total_chars / remaining_width) as u16
-                    + (self.cursor_pos / remaining_width) as u16
+                sidebar_width + 1 + cursor_column as u16,
+                terminal_height - (rows - cursor_row - 1) as u16
             )
         );
+        if self.switcher.is_some() {
+            self.draw_switcher(render_buffer, terminal_width, terminal_height);
+        }
+        if self.ring_bell {
+            render_buffer.push('\x07');
+            self.ring_bell = false;
+        }
         {
             use std::io::Write;
             let out = ::std::io::stdout();
@@ -570,43 +1682,432 @@ impl Tui {
         }
     }
 
+    /// Draw the servers across the top, returning how many rows that took. Unless it wraps, the
+    /// bar scrolls to keep the current server in view, with arrows for the ones off either end.
+    fn draw_server_bar(&mut self, render_buffer: &mut String, width: usize) -> u16 {
+        use std::fmt::Write;
+        use termion::color::Fg;
+        use termion::cursor::Goto;
+        use termion::{color, style};
+
+        let current = self.servers.tell();
+        let mut entries = Vec::new();
+        for (s, server) in self.servers.iter().enumerate() {
+            let mut text = String::new();
+            let mut plain = String::new();
+            match server.state {
+                ConnectionState::Connected => {}
+                ConnectionState::Reconnecting => {
+                    let _ = write!(text, "{}~{}", Fg(color::Yellow), Fg(color::Reset));
+                    plain.push('~');
+                }
+                ConnectionState::Disconnected => {
+                    let _ = write!(text, "{}!{}", Fg(color::Red), Fg(color::Reset));
+                    plain.push('!');
+                }
+            }
+            let name_style = if s == current {
+                style::Bold.to_string()
+            } else if server.has_unreads() {
+                Fg(color::Red).to_string()
+            } else {
+                Fg(color::AnsiValue::rgb(3, 3, 3)).to_string()
+            };
+            let _ = write!(text, "{}{}{}", name_style, server.name, style::Reset);
+            plain.push_str(&server.name);
+            let unreads = server.channels.iter().map(Channel::num_unreads).sum::<usize>();
+            let mentions = server.channels.iter().map(Channel::num_mentions).sum::<usize>();
+            if unreads > 0 && s != current {
+                let _ = write!(text, " {}{}", Fg(color::Red), unreads);
+                let _ = write!(plain, " {}", unreads);
+                if mentions > 0 {
+                    let _ = write!(text, "{}@{}", Fg(color::Yellow), mentions);
+                    let _ = write!(plain, "@{}", mentions);
+                }
+                let _ = write!(text, "{}", Fg(color::Reset));
+            }
+            entries.push((text, ::layout::width(&plain)));
+        }
+
+        let left = self.sidebar.width + 1;
+        let separator = SERVER_SEPARATOR.chars().count();
+        self.server_bar_hits.clear();
+
+        if self.wrap_server_bar {
+            let mut row = 1;
+            let mut column = 0;
+            for (s, (text, entry_width)) in entries.iter().enumerate() {
+                if column > 0 && column + separator + entry_width > width {
+                    row += 1;
+                    column = 0;
+                } else if column > 0 {
+                    let _ = write!(render_buffer, "{}", SERVER_SEPARATOR);
+                    column += separator;
+                }
+                let x = left + column as u16;
+                let _ = write!(render_buffer, "{}{}", Goto(x, row), text);
+                self.server_bar_hits.push((row, x..x + *entry_width as u16, s));
+                column += entry_width;
+            }
+            return row;
+        }
+
+        // How wide the servers from `first` to `last` are, with arrows for any left out
+        let span = |first: usize, last: usize| {
+            entries[first..=last].iter().map(|e| e.1).sum::<usize>()
+                + separator * (last - first)
+                + if first > 0 { 2 } else { 0 }
+                + if last + 1 < entries.len() { 2 } else { 0 }
+        };
+        let mut first = min(self.server_scroll_offset, current);
+        while first < current && span(first, current) > width {
+            first += 1;
+        }
+        let mut last = current;
+        while last + 1 < entries.len() && span(first, last + 1) <= width {
+            last += 1;
+        }
+        self.server_scroll_offset = first;
+
+        let _ = write!(render_buffer, "{}", Goto(left, 1));
+        let mut x = left;
+        // Arrows are red when there are unreads past them
+        let arrow_color = |unreads: bool| {
+            if unreads {
+                Fg(color::Red).to_string()
+            } else {
+                Fg(color::AnsiValue::grayscale(12)).to_string()
+            }
+        };
+        if first > 0 {
+            let colored = arrow_color(self.servers.iter().take(first).any(Server::has_unreads));
+            let _ = write!(render_buffer, "{}«{} ", colored, Fg(color::Reset));
+            self.server_bar_hits.push((1, x..x + 1, first - 1));
+            x += 2;
+        }
+        for (s, (text, entry_width)) in entries.iter().enumerate().take(last + 1).skip(first) {
+            if s > first {
+                render_buffer.push_str(SERVER_SEPARATOR);
+                x += separator as u16;
+            }
+            render_buffer.push_str(text);
+            self.server_bar_hits.push((1, x..x + *entry_width as u16, s));
+            x += *entry_width as u16;
+        }
+        if last + 1 < entries.len() {
+            let colored = arrow_color(self.servers.iter().skip(last + 1).any(Server::has_unreads));
+            let _ = write!(render_buffer, " {}»{}", colored, Fg(color::Reset));
+            self.server_bar_hits.push((1, x + 1..x + 2, last + 1));
+        }
+        1
+    }
+
+    /// Switch to the server whose name was clicked in the server bar
+    fn click_server_bar(&mut self, column: u16, row: u16) -> bool {
+        let clicked = self
+            .server_bar_hits
+            .iter()
+            .find(|(r, columns, _)| *r == row && columns.start <= column && column < columns.end)
+            .map(|hit| hit.2);
+        match clicked {
+            Some(server) => {
+                self.reset_current_unreads();
+                self.servers.set(server);
+                self.channel_changed();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Draw the channels with activity on any server across `row`, returning false if there
+    /// aren't any
+    fn draw_hotlist(&self, render_buffer: &mut String, row: u16, width: usize) -> bool {
+        use std::fmt::Write;
+        use termion::color;
+        use termion::color::Fg;
+        use termion::cursor::Goto;
+
+        let hotlist = self.hotlist();
+        if hotlist.is_empty() {
+            return false;
+        }
+        let _ = write!(
+            render_buffer,
+            "{}{}Act:{}",
+            Goto(self.sidebar.width + 1, row),
+            Fg(color::AnsiValue::grayscale(12)),
+            Fg(color::Reset)
+        );
+        let mut used = "Act:".len();
+        for (i, &(_, _, ref label, unreads, activity)) in hotlist.iter().enumerate() {
+            let entry = format!(" {}({})", label, unreads);
+            let entry_width = ::layout::width(&entry);
+            // Leave room to say how many didn't fit
+            let more = if i + 1 == hotlist.len() { 0 } else { 6 };
+            if used + entry_width + more > width {
+                let _ = write!(render_buffer, " +{}", hotlist.len() - i);
+                break;
+            }
+            match activity {
+                Activity::Direct => {
+                    let _ = write!(render_buffer, "{}", Fg(color::Green));
+                }
+                Activity::Mention => {
+                    let _ = write!(render_buffer, "{}", Fg(color::Yellow));
+                }
+                Activity::Message => {}
+            }
+            let _ = write!(render_buffer, "{}{}", entry, Fg(color::Reset));
+            used += entry_width;
+        }
+        true
+    }
+
+    /// Draw the quick switcher as a popup over the top of the message area, leaving the cursor at
+    /// the end of its query
+    fn draw_switcher(&self, render_buffer: &mut String, terminal_width: u16, terminal_height: u16) {
+        use std::fmt::Write;
+        use termion::color::Fg;
+        use termion::cursor::Goto;
+        use termion::{color, style};
+
+        let switcher = match self.switcher {
+            Some(ref switcher) => switcher,
+            None => return,
+        };
+        let left = self.sidebar.width + 3;
+        let width = min(60, terminal_width.saturating_sub(left + 1)) as usize;
+        let visible = min(10, terminal_height.saturating_sub(6)) as usize;
+        if width < 10 || visible == 0 {
+            return;
+        }
+
+        let entries = self.switcher_entries(&switcher.query);
+        // Keep the selected entry in view
+        let first = switcher.selected.saturating_sub(visible - 1);
+
+        let pad = |render_buffer: &mut String, used: usize| {
+            render_buffer.extend(::std::iter::repeat(' ').take(width.saturating_sub(used)));
+        };
+        let query = ::layout::truncate(&switcher.query, width - 3);
+        let _ = write!(render_buffer, "{}{}> {}", Goto(left, 2), style::Bold, query);
+        pad(render_buffer, 2 + ::layout::width(&query));
+        let _ = write!(render_buffer, "{}", style::Reset);
+
+        let mut row = 3;
+        for (i, entry) in entries.iter().enumerate().skip(first).take(visible) {
+            let label = ::layout::truncate(&entry.label, width - 2);
+            let _ = write!(render_buffer, "{}", Goto(left, row));
+            if i == switcher.selected {
+                let _ = write!(render_buffer, "{}", style::Invert);
+            }
+            match entry.unread {
+                2 => {
+                    let _ = write!(render_buffer, "{}", Fg(color::Yellow));
+                }
+                0 => {
+                    let _ = write!(render_buffer, "{}", Fg(color::AnsiValue::grayscale(12)));
+                }
+                _ => {}
+            }
+            let _ = write!(render_buffer, " {}", label);
+            pad(render_buffer, 1 + ::layout::width(&label));
+            let _ = write!(render_buffer, "{}{}", Fg(color::Reset), style::Reset);
+            row += 1;
+        }
+        if entries.is_empty() {
+            let _ = write!(
+                render_buffer,
+                "{}{} No matches",
+                Goto(left, row),
+                Fg(color::AnsiValue::grayscale(12))
+            );
+            pad(render_buffer, " No matches".len());
+            let _ = write!(render_buffer, "{}", Fg(color::Reset));
+        }
+
+        let cursor = left + 2 + ::layout::width(&query) as u16;
+        let _ = write!(render_buffer, "{}", Goto(cursor, 2));
+    }
+
+    /// Keys for scrolling through messages once focus has moved off the input line
+    /// Returns false if the key should get the usual handling
+    fn handle_message_focus(&mut self, key: ::termion::event::Key) -> bool {
+        use termion::event::Key::*;
+        match key {
+            Char('k') | Up => {
+                self.current_channel_mut().message_scroll_offset += 1;
+            }
+            Char('j') | Down => {
+                let chan = self.current_channel_mut();
+                chan.message_scroll_offset = chan.message_scroll_offset.saturating_sub(1);
+            }
+            Char('u') => {
+                self.current_channel_mut().message_scroll_offset += 10;
+            }
+            Char('d') => {
+                let chan = self.current_channel_mut();
+                chan.message_scroll_offset = chan.message_scroll_offset.saturating_sub(10);
+            }
+            Char('G') => {
+                self.current_channel_mut().message_scroll_offset = 0;
+            }
+            Char('K') => {
+                let newest_first = self.current_channel().messages.len().saturating_sub(1);
+                self.selected_message = min(self.selected_message + 1, newest_first);
+                self.reveal_selection = true;
+            }
+            Char('J') => {
+                self.selected_message = self.selected_message.saturating_sub(1);
+                self.reveal_selection = true;
+            }
+            Char('U') => {
+                self.mark_unread_from_selected();
+                self.reveal_selection = true;
+            }
+            Char('i') | Char('a') => {
+                self.focus = Focus::Input;
+                if let Some(ref mut vi) = self.vi {
+                    vi.enter_insert_mode();
+                }
+            }
+            Char('\n') | Esc | Alt('m') => {
+                self.focus = Focus::Input;
+            }
+            // Don't type into an input line that isn't focused
+            Char(_) => {}
+            _ => return false,
+        }
+        true
+    }
+
     fn handle_input(&mut self, event: &::termion::event::Event) {
         use termion::event::Event::*;
         use termion::event::Key::*;
         use termion::event::{MouseButton, MouseEvent};
 
+        if let Key(key) = *event {
+            if self.handle_switcher(key) {
+                return;
+            }
+            if self.handle_history_search(key) {
+                return;
+            }
+            if self.focus == Focus::Messages {
+                if self.handle_message_focus(key) {
+                    return;
+                }
+            } else if let Some(ref mut vi) = self.vi {
+                let server = self.servers.get_mut();
+                let channel = &mut server.channels[server.current_channel];
+                let before = channel.message_buffer.clone();
+                match vi.handle_key(key, &mut channel.message_buffer, &mut self.cursor_pos) {
+                    ::vi::Action::Handled => {
+                        self.autocompletions.clear();
+                        self.autocomplete_index = 0;
+                        // An edited entry is a new line, Up shouldn't throw it away
+                        if channel.message_buffer != before {
+                            self.history_position = None;
+                        }
+                        return;
+                    }
+                    ::vi::Action::Unfocus => {
+                        self.focus = Focus::Messages;
+                        self.selected_message = 0;
+                        return;
+                    }
+                    ::vi::Action::Passthrough => {}
+                }
+            }
+        }
+
         match *event {
             Key(Char('\n')) => {
                 if !self.current_channel().message_buffer.is_empty() {
-                    self.send_message();
-                    self.current_channel_mut().message_buffer.clear();
+                    {
+                        let server = self.servers.get();
+                        let channel = &server.channels[server.current_channel];
+                        self.history
+                            .push(&server.name, &channel.name, &channel.message_buffer);
+                    }
+                    self.history_position = None;
                     self.cursor_pos = 0;
+                    // Take the line first, commands like /buffer can switch to another channel
+                    let contents = ::std::mem::replace(
+                        &mut self.current_channel_mut().message_buffer,
+                        String::new(),
+                    );
+                    self.send_message(contents);
                 }
             }
             Key(Backspace) => {
                 if self.cursor_pos > 0 {
-                    let remove_pos = self.cursor_pos as usize - 1;
-                    self.current_channel_mut().message_buffer.remove(remove_pos);
-                    self.cursor_pos -= 1;
+                    let remove_pos =
+                        ::layout::prev_grapheme(&self.current_channel().message_buffer, self.cursor_pos);
+                    let end = self.cursor_pos;
+                    self.current_channel_mut()
+                        .message_buffer
+                        .drain(remove_pos..end);
+                    self.cursor_pos = remove_pos;
+                    self.history_position = None;
                 }
             }
             Key(Delete) => {
-                let buffer_chars = self.current_channel().message_buffer.chars().count();
-                if buffer_chars > 0 && self.cursor_pos < buffer_chars {
-                    let remove_pos = self.cursor_pos;
-                    self.current_channel_mut().message_buffer.remove(remove_pos);
+                let start = self.cursor_pos;
+                let end = ::layout::next_grapheme(&self.current_channel().message_buffer, start);
+                if start < end {
+                    self.current_channel_mut().message_buffer.drain(start..end);
+                    self.history_position = None;
                 }
             }
             Key(Ctrl('c')) => self.shutdown = true,
+            // Esc is left alone, vi mode uses it and it's the prefix of every Alt key
+            Key(Alt('m')) => {
+                self.focus = Focus::Messages;
+                self.selected_message = 0;
+            }
             Key(Up) => {
-                self.previous_channel();
+                self.recall_history(true);
             }
             Key(Down) => {
-                self.next_channel();
+                self.recall_history(false);
+            }
+            Key(Ctrl('r')) => {
+                self.history_search = Some(::history::Search {
+                    query: String::new(),
+                    skip: 0,
+                    original: self.current_channel().message_buffer.clone(),
+                });
             }
             Key(Ctrl('d')) => {
                 self.next_server();
             }
+            Key(Ctrl('n')) => {
+                self.next_unread();
+            }
+            Key(Ctrl('k')) => {
+                self.switcher = Some(::switcher::Switcher::default());
+            }
+            Key(Alt(c @ '1'..='9')) => {
+                self.go_to_buffer(c as usize - '0' as usize);
+            }
+            Key(Alt('v')) => self.split_pane(Direction::Vertical),
+            Key(Alt('s')) => self.split_pane(Direction::Horizontal),
+            Key(Alt('x')) => self.close_pane(),
+            Key(Alt('o')) => self.cycle_pane(true),
+            // Alt-O can't be told apart from the start of an escape sequence
+            Key(Alt('p')) => self.cycle_pane(false),
+            Key(Alt('+')) | Key(Alt('=')) => self.resize_pane(5),
+            Key(Alt('-')) => self.resize_pane(-5),
+            Key(Alt('<')) => {
+                let width = self.sidebar.width.saturating_sub(1);
+                self.set_sidebar_width(width);
+            }
+            Key(Alt('>')) => {
+                let width = self.sidebar.width + 1;
+                self.set_sidebar_width(width);
+            }
             Key(Ctrl('a')) => {
                 self.previous_server();
             }
@@ -616,6 +2117,9 @@ impl Tui {
             Key(PageUp) | Key(Ctrl('w')) => {
                 self.previous_channel_unread();
             }
+            Mouse(MouseEvent::Press(MouseButton::Left, column, row)) => {
+                self.click_server_bar(column, row);
+            }
             Key(Ctrl('q')) | Mouse(MouseEvent::Press(MouseButton::WheelUp, ..)) => {
                 self.current_channel_mut().message_scroll_offset += 1;
             }
@@ -625,14 +2129,12 @@ impl Tui {
                 chan.message_scroll_offset = previous_offset.saturating_sub(1);
             }
             Key(Left) => {
-                if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
-                }
+                self.cursor_pos =
+                    ::layout::prev_grapheme(&self.current_channel().message_buffer, self.cursor_pos);
             }
             Key(Right) => {
-                if self.cursor_pos < self.current_channel().message_buffer.len() {
-                    self.cursor_pos += 1;
-                }
+                self.cursor_pos =
+                    ::layout::next_grapheme(&self.current_channel().message_buffer, self.cursor_pos);
             }
             Key(Char('\t')) => {
                 if self.autocompletions.is_empty() {
@@ -667,11 +2169,12 @@ impl Tui {
             Key(Char(c)) => {
                 self.autocompletions.clear();
                 self.autocomplete_index = 0;
+                self.history_position = None;
                 let current_pos = self.cursor_pos as usize;
                 self.current_channel_mut()
                     .message_buffer
                     .insert(current_pos, c);
-                self.cursor_pos += 1;
+                self.cursor_pos += c.len_utf8();
             }
             Unsupported(ref bytes) => match bytes.as_slice() {
                 [27, 79, 65] => {
@@ -688,6 +2191,16 @@ impl Tui {
                         1,
                     ))));
                 }
+                // Alt or Ctrl with Up/Down switches channels, the bare keys recall history
+                [27, 91, 49, 59, 51, 65] | [27, 91, 49, 59, 53, 65] => {
+                    self.previous_channel();
+                }
+                [27, 91, 49, 59, 51, 66] | [27, 91, 49, 59, 53, 66] => {
+                    self.next_channel();
+                }
+                // Focus in and out, from focus reporting
+                [27, 91, 73] => self.terminal_focused = true,
+                [27, 91, 79] => self.terminal_focused = false,
 
                 _ => {}
             },
@@ -789,6 +2302,9 @@ impl Tui {
                     );
                 }
             }
+            Event::ChannelJoined { server, channel } => {
+                self.add_channel(&server, channel);
+            }
             Event::Error(message) => {
                 self.add_client_message(message);
             }
@@ -803,12 +2319,34 @@ impl Tui {
                 .and_then(|server| server.channels.iter_mut().find(|c| c.name == channel))
             {
                 c.read_at = read_at;
+                c.loaded = true;
             } else {
                 error!("Failed to load history from {}, {}", channel, server);
             },
+            Event::ReadState {
+                server,
+                channel,
+                read_at,
+                unreads,
+            } => {
+                if let Some(c) = self
+                    .servers
+                    .iter_mut()
+                    .find(|s| s.name == server)
+                    .and_then(|server| server.channels.iter_mut().find(|c| c.name == channel))
+                {
+                    c.read_at = read_at;
+                    c.unloaded_unreads = unreads.unwrap_or(0);
+                }
+            }
             Event::Connected(conn) => {
                 self.add_server(conn);
             }
+            Event::ConnectionChanged { server, state } => {
+                if let Some(s) = self.servers.iter_mut().find(|s| s.name == server) {
+                    s.state = state;
+                }
+            }
             Event::MarkChannelRead {
                 server,
                 channel,
@@ -829,12 +2367,36 @@ impl Tui {
         }
     }
 
+    /// Write out every non-empty input line, if any have changed since the last save
+    fn save_drafts(&mut self) {
+        let mut drafts = self.pending_drafts.clone();
+        for server in self.servers.iter() {
+            let channels: BTreeMap<String, String> = server
+                .channels
+                .iter()
+                .filter(|c| !c.message_buffer.is_empty())
+                .map(|c| (c.name.to_string(), c.message_buffer.clone()))
+                .collect();
+            if !channels.is_empty() {
+                drafts
+                    .entry(server.name.to_string())
+                    .or_default()
+                    .extend(channels);
+            }
+        }
+        if drafts != self.saved_drafts {
+            ::state::save(DRAFTS_FILE, &drafts);
+            self.saved_drafts = drafts;
+        }
+    }
+
     // This is basically a game loop, we could use a temporary storage allocator
     // If that were possible
     pub fn run(mut self) {
         use std::time::{Duration, Instant};
         let mut render_buffer = String::new();
         self.draw(&mut render_buffer);
+        let mut drafts_saved_at = Instant::now();
         while let Ok(event) = self.events.recv() {
             self.handle_event(event);
 
@@ -856,11 +2418,19 @@ impl Tui {
             }
 
             self.draw(&mut render_buffer);
+            self.note_seen();
+
+            if drafts_saved_at.elapsed() > DRAFT_SAVE_INTERVAL {
+                self.save_drafts();
+                drafts_saved_at = Instant::now();
+            }
 
             if self.shutdown {
                 break;
             }
         }
+        self.save_drafts();
+        self.save_session();
     }
 }
 
@@ -892,6 +2462,7 @@ impl Conn for ClientConn {
             is_mention: false,
             timestamp: ::chrono::Utc::now().into(),
             reactions: Vec::new(),
+            dialect: ::markup::Dialect::Plain,
         }));
     }
 
@@ -899,3 +2470,79 @@ impl Conn for ClientConn {
         &self.channel_names
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(seconds: i64) -> DateTime {
+        DateTime::from(::slack::Timestamp {
+            microseconds: seconds * 1_000_000,
+        })
+    }
+
+    fn message(seconds: i64, text: &str) -> Message {
+        Message {
+            server: "Work".into(),
+            channel: "general".into(),
+            contents: text.into(),
+            is_mention: false,
+            timestamp: at(seconds),
+            sender: "bob".into(),
+            reactions: Vec::new(),
+            dialect: ::markup::Dialect::Plain,
+        }
+    }
+
+    fn channel(messages: Vec<ChanMessage>) -> Channel {
+        Channel {
+            messages,
+            name: "general".into(),
+            number: 1,
+            kind: ChannelKind::Public,
+            read_at: at(100),
+            message_scroll_offset: 0,
+            message_buffer: String::new(),
+            loaded: true,
+            unloaded_unreads: 0,
+            keep_unread: false,
+            seen_at: None,
+        }
+    }
+
+    #[test]
+    fn marking_unread_from_a_message() {
+        let mut channel = channel(vec![
+            ChanMessage::from(message(1, "one")),
+            ChanMessage::from(message(2, "two")),
+            ChanMessage::local(message(3, "All caught up")),
+            ChanMessage::from(message(4, "four")),
+        ]);
+        assert_eq!(channel.mark_unread_from(0), Some(at(2)));
+        assert_eq!(channel.num_unreads(), 1);
+        assert!(channel.keep_unread);
+        // Marking from the client's notice goes by the backend message before it
+        assert_eq!(channel.mark_unread_from(1), Some(at(2)));
+        assert_eq!(channel.mark_unread_from(2), Some(at(1)));
+        assert_eq!(channel.num_unreads(), 2);
+
+        let first = channel.mark_unread_from(3).unwrap();
+        assert!(first < at(1));
+        assert_eq!(channel.num_unreads(), 3);
+        assert_eq!(channel.mark_unread_from(4), None);
+    }
+
+    #[test]
+    fn selected_message_is_scrolled_into_view() {
+        let mut channel = channel((1..=10).map(|s| message(s, "hi").into()).collect());
+        // Each message is one line, and with nothing unread there's no marker
+        channel.scroll_to_message(7, 80, 5);
+        assert_eq!(channel.message_scroll_offset, 3);
+        channel.scroll_to_message(5, 80, 5);
+        assert_eq!(channel.message_scroll_offset, 3);
+        channel.scroll_to_message(1, 80, 5);
+        assert_eq!(channel.message_scroll_offset, 1);
+        channel.scroll_to_message(20, 80, 5);
+        assert_eq!(channel.message_scroll_offset, 1);
+    }
+}
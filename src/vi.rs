@@ -0,0 +1,935 @@
+//! Optional vi-style modal editing for the message input line.
+//!
+//! The editor works directly on a channel's message buffer and the TUI's cursor, which is a byte
+//! offset that always sits on a char boundary.
+use termion::event::Key;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    Insert,
+    Visual,
+}
+
+impl Mode {
+    pub fn indicator(self) -> &'static str {
+        match self {
+            Mode::Normal => "-- NORMAL --",
+            Mode::Insert => "-- INSERT --",
+            Mode::Visual => "-- VISUAL --",
+        }
+    }
+}
+
+/// What the TUI should do with a key once the editor has looked at it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// The editor consumed the key
+    Handled,
+    /// The key isn't an editing command, handle it like any other input
+    Passthrough,
+    /// Escape in normal mode with nothing to edit, focus should move off the input line
+    Unfocus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Find {
+    Forward,
+    Backward,
+    TillForward,
+    TillBackward,
+}
+
+impl Find {
+    fn reversed(self) -> Find {
+        match self {
+            Find::Forward => Find::Backward,
+            Find::Backward => Find::Forward,
+            Find::TillForward => Find::TillBackward,
+            Find::TillBackward => Find::TillForward,
+        }
+    }
+}
+
+/// Commands that need one more character before they can run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    Find(Find),
+    Replace,
+    Object { inner: bool },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Motion {
+    Left,
+    Right,
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    WordForward { big: bool },
+    WordBackward { big: bool },
+    WordEnd { big: bool },
+    Find(Find, char),
+    RepeatFind { reverse: bool },
+}
+
+/// Counts past this are cut down to it, so a held digit key can't make `p` or a motion run away
+const MAX_COUNT: usize = 9999;
+
+pub struct ViEditor {
+    mode: Mode,
+    count: Option<usize>,
+    operator: Option<(Operator, usize)>,
+    pending: Option<Pending>,
+    last_find: Option<(Find, char)>,
+    register: String,
+    visual_anchor: usize,
+    undo: Vec<(String, usize)>,
+}
+
+impl ViEditor {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Insert,
+            count: None,
+            operator: None,
+            pending: None,
+            last_find: None,
+            register: String::new(),
+            visual_anchor: 0,
+            undo: Vec::new(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// The byte range that is highlighted in visual mode
+    pub fn selection(&self, buffer: &str, cursor: usize) -> Option<(usize, usize)> {
+        if self.mode == Mode::Visual && !buffer.is_empty() {
+            let start = ::std::cmp::min(self.visual_anchor, cursor);
+            let end = ::std::cmp::max(self.visual_anchor, cursor);
+            Some((start, next_boundary(buffer, end)))
+        } else {
+            None
+        }
+    }
+
+    /// Forget undo history and half-typed commands, used when the buffer is swapped out from
+    /// under the editor by switching channels
+    pub fn buffer_changed(&mut self) {
+        if self.mode == Mode::Visual {
+            self.mode = Mode::Normal;
+        }
+        self.clear_pending();
+        self.undo.clear();
+    }
+
+    pub fn handle_key(&mut self, key: Key, buffer: &mut String, cursor: &mut usize) -> Action {
+        match self.mode {
+            Mode::Insert => self.handle_insert(key, buffer, cursor),
+            Mode::Normal | Mode::Visual => self.handle_command(key, buffer, cursor),
+        }
+    }
+
+    pub fn enter_insert_mode(&mut self) {
+        self.mode = Mode::Insert;
+        self.clear_pending();
+    }
+
+    fn handle_insert(&mut self, key: Key, buffer: &mut String, cursor: &mut usize) -> Action {
+        match key {
+            Key::Esc => {
+                self.mode = Mode::Normal;
+                *cursor = prev_boundary(buffer, *cursor);
+                Action::Handled
+            }
+            _ => Action::Passthrough,
+        }
+    }
+
+    fn clear_pending(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.pending = None;
+    }
+
+    fn take_count(&mut self) -> usize {
+        self.count.take().unwrap_or(1)
+    }
+
+    fn push_count_digit(&mut self, digit: usize) {
+        let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+        self.count = Some(count.min(MAX_COUNT));
+    }
+
+    fn save_undo(&mut self, buffer: &str, cursor: usize) {
+        self.undo.push((buffer.to_string(), cursor));
+        if self.undo.len() > 100 {
+            self.undo.remove(0);
+        }
+    }
+
+    fn enter_insert(&mut self, buffer: &str, cursor: usize) {
+        self.save_undo(buffer, cursor);
+        self.mode = Mode::Insert;
+        self.clear_pending();
+    }
+
+    fn handle_command(&mut self, key: Key, buffer: &mut String, cursor: &mut usize) -> Action {
+        use termion::event::Key::*;
+
+        if let Some(pending) = self.pending.take() {
+            return match key {
+                Char(c) => self.finish_pending(pending, c, buffer, cursor),
+                _ => {
+                    self.clear_pending();
+                    Action::Handled
+                }
+            };
+        }
+
+        let c = match key {
+            Char('\n') => {
+                self.clear_pending();
+                self.mode = Mode::Insert;
+                return Action::Passthrough;
+            }
+            Char(c) => c,
+            Esc => {
+                let had_pending = self.count.is_some() || self.operator.is_some();
+                self.clear_pending();
+                if self.mode == Mode::Visual {
+                    self.mode = Mode::Normal;
+                    return Action::Handled;
+                }
+                return if buffer.is_empty() && !had_pending {
+                    Action::Unfocus
+                } else {
+                    Action::Handled
+                };
+            }
+            Left | Backspace => 'h',
+            Right => 'l',
+            Home => '0',
+            End => '$',
+            Delete => 'x',
+            _ => return Action::Passthrough,
+        };
+
+        match c {
+            '1'..='9' => {
+                let digit = c.to_digit(10).unwrap() as usize;
+                self.push_count_digit(digit);
+                return Action::Handled;
+            }
+            '0' if self.count.is_some() => {
+                self.push_count_digit(0);
+                return Action::Handled;
+            }
+            _ => {}
+        }
+
+        let motion = match c {
+            'h' => Some(Motion::Left),
+            'l' | ' ' => Some(Motion::Right),
+            '0' => Some(Motion::LineStart),
+            '^' => Some(Motion::FirstNonBlank),
+            '$' => Some(Motion::LineEnd),
+            'w' => Some(Motion::WordForward { big: false }),
+            'W' => Some(Motion::WordForward { big: true }),
+            'b' => Some(Motion::WordBackward { big: false }),
+            'B' => Some(Motion::WordBackward { big: true }),
+            'e' => Some(Motion::WordEnd { big: false }),
+            'E' => Some(Motion::WordEnd { big: true }),
+            ';' => Some(Motion::RepeatFind { reverse: false }),
+            ',' => Some(Motion::RepeatFind { reverse: true }),
+            _ => None,
+        };
+        if let Some(motion) = motion {
+            return self.run_motion(motion, buffer, cursor);
+        }
+
+        match c {
+            'f' => self.pending = Some(Pending::Find(Find::Forward)),
+            'F' => self.pending = Some(Pending::Find(Find::Backward)),
+            't' => self.pending = Some(Pending::Find(Find::TillForward)),
+            'T' => self.pending = Some(Pending::Find(Find::TillBackward)),
+            'i' | 'a' if self.operator.is_some() || self.mode == Mode::Visual => {
+                self.pending = Some(Pending::Object { inner: c == 'i' })
+            }
+            _ if self.mode == Mode::Visual => return self.handle_visual(c, buffer, cursor),
+            _ => return self.handle_normal(c, buffer, cursor),
+        }
+        Action::Handled
+    }
+
+    fn handle_normal(&mut self, c: char, buffer: &mut String, cursor: &mut usize) -> Action {
+        let operator = match c {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        };
+
+        if let Some(operator) = operator {
+            match self.operator {
+                // dd, cc and yy operate on the whole input line
+                Some((pending, _)) if pending == operator => {
+                    self.operator = None;
+                    self.count = None;
+                    let len = buffer.len();
+                    return self.apply_operator(operator, 0, len, buffer, cursor);
+                }
+                Some(_) => self.clear_pending(),
+                None => {
+                    let count = self.take_count();
+                    self.operator = Some((operator, count));
+                }
+            }
+            return Action::Handled;
+        }
+
+        // Anything else cancels a half-typed operator
+        if self.operator.is_some() {
+            self.clear_pending();
+            return Action::Handled;
+        }
+
+        let count = self.take_count();
+        match c {
+            'i' => self.enter_insert(buffer, *cursor),
+            'a' => {
+                self.enter_insert(buffer, *cursor);
+                *cursor = next_boundary(buffer, *cursor);
+            }
+            'I' => {
+                self.enter_insert(buffer, *cursor);
+                *cursor = first_non_blank(buffer);
+            }
+            'A' => {
+                self.enter_insert(buffer, *cursor);
+                *cursor = buffer.len();
+            }
+            'v' => {
+                self.mode = Mode::Visual;
+                self.visual_anchor = *cursor;
+            }
+            'x' => {
+                let end = advance(buffer, *cursor, count);
+                return self.apply_operator(Operator::Delete, *cursor, end, buffer, cursor);
+            }
+            'X' => {
+                let start = retreat(buffer, *cursor, count);
+                return self.apply_operator(Operator::Delete, start, *cursor, buffer, cursor);
+            }
+            's' => {
+                let end = advance(buffer, *cursor, count);
+                return self.apply_operator(Operator::Change, *cursor, end, buffer, cursor);
+            }
+            'S' => {
+                let len = buffer.len();
+                return self.apply_operator(Operator::Change, 0, len, buffer, cursor);
+            }
+            'D' => {
+                let len = buffer.len();
+                return self.apply_operator(Operator::Delete, *cursor, len, buffer, cursor);
+            }
+            'C' => {
+                let len = buffer.len();
+                return self.apply_operator(Operator::Change, *cursor, len, buffer, cursor);
+            }
+            'Y' => {
+                self.register = buffer.clone();
+            }
+            'p' | 'P' => {
+                if !self.register.is_empty() {
+                    self.save_undo(buffer, *cursor);
+                    let at = if c == 'p' {
+                        next_boundary(buffer, *cursor)
+                    } else {
+                        *cursor
+                    };
+                    let text = self.register.repeat(count);
+                    buffer.insert_str(at, &text);
+                    *cursor = prev_boundary(buffer, at + text.len());
+                }
+            }
+            'r' => {
+                self.count = Some(count);
+                self.pending = Some(Pending::Replace);
+            }
+            '~' => {
+                if !buffer.is_empty() {
+                    self.save_undo(buffer, *cursor);
+                    let end = advance(buffer, *cursor, count);
+                    let toggled = toggle_case(&buffer[*cursor..end]);
+                    buffer.replace_range(*cursor..end, &toggled);
+                    *cursor = clamp_normal(buffer, *cursor + toggled.len());
+                }
+            }
+            'u' => {
+                if let Some((previous, previous_cursor)) = self.undo.pop() {
+                    *buffer = previous;
+                    *cursor = clamp_normal(buffer, previous_cursor);
+                }
+            }
+            _ => {}
+        }
+        Action::Handled
+    }
+
+    fn handle_visual(&mut self, c: char, buffer: &mut String, cursor: &mut usize) -> Action {
+        let (start, end) = match self.selection(buffer, *cursor) {
+            Some(range) => range,
+            None => {
+                if c == 'v' {
+                    self.mode = Mode::Normal;
+                }
+                return Action::Handled;
+            }
+        };
+        self.count = None;
+        match c {
+            'v' => self.mode = Mode::Normal,
+            'o' => ::std::mem::swap(&mut self.visual_anchor, cursor),
+            'd' | 'x' => {
+                self.mode = Mode::Normal;
+                return self.apply_operator(Operator::Delete, start, end, buffer, cursor);
+            }
+            'c' | 's' => return self.apply_operator(Operator::Change, start, end, buffer, cursor),
+            'y' => {
+                self.mode = Mode::Normal;
+                return self.apply_operator(Operator::Yank, start, end, buffer, cursor);
+            }
+            '~' => {
+                self.save_undo(buffer, *cursor);
+                let toggled = toggle_case(&buffer[start..end]);
+                buffer.replace_range(start..end, &toggled);
+                self.mode = Mode::Normal;
+                *cursor = clamp_normal(buffer, start);
+            }
+            _ => {}
+        }
+        Action::Handled
+    }
+
+    fn finish_pending(
+        &mut self,
+        pending: Pending,
+        c: char,
+        buffer: &mut String,
+        cursor: &mut usize,
+    ) -> Action {
+        match pending {
+            Pending::Find(find) => {
+                self.last_find = Some((find, c));
+                self.run_motion(Motion::Find(find, c), buffer, cursor)
+            }
+            Pending::Replace => {
+                let count = self.take_count();
+                let end = advance(buffer, *cursor, count);
+                if buffer[*cursor..end].chars().count() == count {
+                    self.save_undo(buffer, *cursor);
+                    let replacement: String = ::std::iter::repeat(c).take(count).collect();
+                    buffer.replace_range(*cursor..end, &replacement);
+                    *cursor = prev_boundary(buffer, *cursor + replacement.len());
+                }
+                Action::Handled
+            }
+            Pending::Object { inner } => {
+                let range = text_object(buffer, *cursor, inner, c);
+                match (range, self.operator.take()) {
+                    (Some((start, end)), Some((operator, _))) => {
+                        self.count = None;
+                        self.apply_operator(operator, start, end, buffer, cursor)
+                    }
+                    (Some((start, end)), None) => {
+                        if start < end {
+                            self.visual_anchor = start;
+                            *cursor = prev_boundary(buffer, end);
+                        }
+                        Action::Handled
+                    }
+                    (None, _) => {
+                        self.clear_pending();
+                        Action::Handled
+                    }
+                }
+            }
+        }
+    }
+
+    fn run_motion(&mut self, motion: Motion, buffer: &mut String, cursor: &mut usize) -> Action {
+        let count = self.take_count();
+        match self.operator.take() {
+            Some((operator, operator_count)) => {
+                let count = count.saturating_mul(operator_count).min(MAX_COUNT);
+                // cw behaves like ce when it starts on a word, like vim
+                let motion = match (operator, motion) {
+                    (Operator::Change, Motion::WordForward { big })
+                        if buffer[*cursor..]
+                            .chars()
+                            .next()
+                            .map_or(false, |c| !c.is_whitespace()) =>
+                    {
+                        Motion::WordEnd { big }
+                    }
+                    _ => motion,
+                };
+                if let Some((target, inclusive)) =
+                    self.motion_target(buffer, *cursor, motion, count)
+                {
+                    let (start, end) = if target < *cursor {
+                        (target, *cursor)
+                    } else if inclusive {
+                        (*cursor, next_boundary(buffer, target))
+                    } else {
+                        (*cursor, target)
+                    };
+                    return self.apply_operator(operator, start, end, buffer, cursor);
+                }
+            }
+            None => {
+                if let Some((target, _)) = self.motion_target(buffer, *cursor, motion, count) {
+                    *cursor = clamp_normal(buffer, target);
+                }
+            }
+        }
+        Action::Handled
+    }
+
+    fn motion_target(
+        &self,
+        buffer: &str,
+        cursor: usize,
+        motion: Motion,
+        count: usize,
+    ) -> Option<(usize, bool)> {
+        let mut pos = cursor;
+        match motion {
+            Motion::Left => return Some((retreat(buffer, cursor, count), false)),
+            Motion::Right => return Some((advance(buffer, cursor, count), false)),
+            Motion::LineStart => return Some((0, false)),
+            Motion::FirstNonBlank => return Some((first_non_blank(buffer), false)),
+            Motion::LineEnd => return Some((buffer.len(), false)),
+            Motion::WordForward { big } => {
+                for _ in 0..count {
+                    pos = word_forward(buffer, pos, big);
+                }
+                return Some((pos, false));
+            }
+            Motion::WordBackward { big } => {
+                for _ in 0..count {
+                    pos = word_backward(buffer, pos, big);
+                }
+                return Some((pos, false));
+            }
+            Motion::WordEnd { big } => {
+                for _ in 0..count {
+                    pos = word_end(buffer, pos, big);
+                }
+                return Some((pos, true));
+            }
+            Motion::Find(find, c) => {
+                for _ in 0..count {
+                    pos = find_char(buffer, pos, find, c)?;
+                }
+                let inclusive = find == Find::Forward || find == Find::TillForward;
+                Some((pos, inclusive))
+            }
+            Motion::RepeatFind { reverse } => {
+                let (find, c) = self.last_find?;
+                let find = if reverse { find.reversed() } else { find };
+                self.motion_target(buffer, cursor, Motion::Find(find, c), count)
+            }
+        }
+    }
+
+    fn apply_operator(
+        &mut self,
+        operator: Operator,
+        start: usize,
+        end: usize,
+        buffer: &mut String,
+        cursor: &mut usize,
+    ) -> Action {
+        self.register = buffer[start..end].to_string();
+        match operator {
+            Operator::Yank => {
+                *cursor = clamp_normal(buffer, start);
+            }
+            Operator::Delete => {
+                self.save_undo(buffer, *cursor);
+                buffer.replace_range(start..end, "");
+                *cursor = clamp_normal(buffer, start);
+            }
+            Operator::Change => {
+                self.save_undo(buffer, *cursor);
+                buffer.replace_range(start..end, "");
+                *cursor = start;
+                self.mode = Mode::Insert;
+            }
+        }
+        self.clear_pending();
+        Action::Handled
+    }
+}
+
+use layout::{next_grapheme as next_boundary, prev_grapheme as prev_boundary};
+
+fn advance(buffer: &str, pos: usize, count: usize) -> usize {
+    (0..count).fold(pos, |p, _| next_boundary(buffer, p))
+}
+
+fn retreat(buffer: &str, pos: usize, count: usize) -> usize {
+    (0..count).fold(pos, |p, _| prev_boundary(buffer, p))
+}
+
+/// In normal mode the cursor sits on a character, never after the last one
+fn clamp_normal(buffer: &str, pos: usize) -> usize {
+    if pos >= buffer.len() {
+        prev_boundary(buffer, buffer.len())
+    } else {
+        pos
+    }
+}
+
+fn first_non_blank(buffer: &str) -> usize {
+    buffer
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map_or(0, |(i, _)| i)
+}
+
+fn toggle_case(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| {
+            if c.is_uppercase() {
+                c.to_lowercase().collect::<Vec<_>>()
+            } else {
+                c.to_uppercase().collect::<Vec<_>>()
+            }
+        })
+        .collect()
+}
+
+/// 0 for whitespace, 1 for word characters, 2 for punctuation
+/// WORDs (`big`) lump everything that isn't whitespace together
+fn class(c: char, big: bool) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if big || c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn word_forward(buffer: &str, pos: usize, big: bool) -> usize {
+    let mut chars = buffer[pos..].char_indices().map(|(i, c)| (i + pos, c)).peekable();
+    let start_class = match chars.peek() {
+        Some(&(_, c)) => class(c, big),
+        None => return buffer.len(),
+    };
+    if start_class != 0 {
+        while chars.peek().map_or(false, |&(_, c)| class(c, big) == start_class) {
+            chars.next();
+        }
+    }
+    while chars.peek().map_or(false, |&(_, c)| c.is_whitespace()) {
+        chars.next();
+    }
+    chars.peek().map_or(buffer.len(), |&(i, _)| i)
+}
+
+fn word_end(buffer: &str, pos: usize, big: bool) -> usize {
+    let start = next_boundary(buffer, pos);
+    let mut chars = buffer[start..]
+        .char_indices()
+        .map(|(i, c)| (i + start, c))
+        .skip_while(|&(_, c)| c.is_whitespace())
+        .peekable();
+    let (mut end, end_class) = match chars.next() {
+        Some((i, c)) => (i, class(c, big)),
+        None => return pos,
+    };
+    while let Some(&(i, c)) = chars.peek() {
+        if class(c, big) != end_class {
+            break;
+        }
+        end = i;
+        chars.next();
+    }
+    end
+}
+
+fn word_backward(buffer: &str, pos: usize, big: bool) -> usize {
+    let mut chars = buffer[..pos]
+        .char_indices()
+        .rev()
+        .skip_while(|&(_, c)| c.is_whitespace())
+        .peekable();
+    let (mut start, start_class) = match chars.next() {
+        Some((i, c)) => (i, class(c, big)),
+        None => return 0,
+    };
+    while let Some(&(i, c)) = chars.peek() {
+        if class(c, big) != start_class {
+            break;
+        }
+        start = i;
+        chars.next();
+    }
+    start
+}
+
+fn find_char(buffer: &str, pos: usize, find: Find, target: char) -> Option<usize> {
+    match find {
+        Find::Forward | Find::TillForward => {
+            let from = next_boundary(buffer, pos);
+            // Repeating t shouldn't get stuck on the character it already stopped in front of
+            let from = if find == Find::TillForward && buffer[from..].starts_with(target) {
+                next_boundary(buffer, from)
+            } else {
+                from
+            };
+            let found = buffer[from..].find(target)? + from;
+            if find == Find::TillForward {
+                Some(prev_boundary(buffer, found))
+            } else {
+                Some(found)
+            }
+        }
+        Find::Backward | Find::TillBackward => {
+            let mut upto = pos;
+            if find == Find::TillBackward && buffer[..pos].ends_with(target) {
+                upto = prev_boundary(buffer, pos);
+            }
+            let found = buffer[..upto].rfind(target)?;
+            if find == Find::TillBackward {
+                Some(next_boundary(buffer, found))
+            } else {
+                Some(found)
+            }
+        }
+    }
+}
+
+fn text_object(buffer: &str, pos: usize, inner: bool, c: char) -> Option<(usize, usize)> {
+    match c {
+        'w' | 'W' => word_object(buffer, pos, inner, c == 'W'),
+        '"' | '\'' | '`' => quote_object(buffer, pos, inner, c),
+        '(' | ')' | 'b' => bracket_object(buffer, pos, inner, '(', ')'),
+        '[' | ']' => bracket_object(buffer, pos, inner, '[', ']'),
+        '{' | '}' | 'B' => bracket_object(buffer, pos, inner, '{', '}'),
+        '<' | '>' => bracket_object(buffer, pos, inner, '<', '>'),
+        _ => None,
+    }
+}
+
+fn word_object(buffer: &str, pos: usize, inner: bool, big: bool) -> Option<(usize, usize)> {
+    let here = buffer[pos..].chars().next()?;
+    let here_class = class(here, big);
+    let start = buffer[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|&(_, c)| class(c, big) == here_class)
+        .last()
+        .map_or(pos, |(i, _)| i);
+    let end = buffer[pos..]
+        .char_indices()
+        .find(|&(_, c)| class(c, big) != here_class)
+        .map_or(buffer.len(), |(i, _)| i + pos);
+    if inner || here_class == 0 {
+        return Some((start, end));
+    }
+    // "a word" takes the trailing whitespace, or the leading whitespace if there is none after
+    let trailing = buffer[end..]
+        .char_indices()
+        .find(|&(_, c)| !c.is_whitespace())
+        .map_or(buffer.len(), |(i, _)| i + end);
+    if trailing > end {
+        Some((start, trailing))
+    } else {
+        let leading = buffer[..start]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| c.is_whitespace())
+            .last()
+            .map_or(start, |(i, _)| i);
+        Some((leading, end))
+    }
+}
+
+fn quote_object(buffer: &str, pos: usize, inner: bool, quote: char) -> Option<(usize, usize)> {
+    let quotes: Vec<usize> = buffer
+        .char_indices()
+        .filter(|&(_, c)| c == quote)
+        .map(|(i, _)| i)
+        .collect();
+    // Quotes pair up from the start of the line, find the pair around or after the cursor
+    let (open, close) = quotes
+        .chunks(2)
+        .filter(|pair| pair.len() == 2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|&(_, close)| close >= pos)?;
+    if inner {
+        Some((open + quote.len_utf8(), close))
+    } else {
+        Some((open, close + quote.len_utf8()))
+    }
+}
+
+fn bracket_object(
+    buffer: &str,
+    pos: usize,
+    inner: bool,
+    open: char,
+    close: char,
+) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut open_pos = None;
+    let search_from = if buffer[pos..].starts_with(close) {
+        pos
+    } else {
+        next_boundary(buffer, pos)
+    };
+    for (i, c) in buffer[..search_from].char_indices().rev() {
+        if c == close && i != pos {
+            depth += 1;
+        } else if c == open {
+            if depth == 0 {
+                open_pos = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let open_pos = open_pos?;
+
+    let mut depth = 0;
+    let after_open = open_pos + open.len_utf8();
+    let close_pos = buffer[after_open..].char_indices().find_map(|(i, c)| {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(i + after_open);
+            }
+            depth -= 1;
+        }
+        None
+    })?;
+
+    if inner {
+        Some((after_open, close_pos))
+    } else {
+        Some((open_pos, close_pos + close.len_utf8()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normal_mode() -> ViEditor {
+        let mut editor = ViEditor::new();
+        editor.mode = Mode::Normal;
+        editor
+    }
+
+    fn press(editor: &mut ViEditor, keys: &str, buffer: &mut String, cursor: &mut usize) {
+        for c in keys.chars() {
+            editor.handle_key(Key::Char(c), buffer, cursor);
+        }
+    }
+
+    /// The buffer and cursor after typing `keys` in normal mode
+    fn run(keys: &str, text: &str, cursor: usize) -> (String, usize) {
+        let mut buffer = String::from(text);
+        let mut cursor = cursor;
+        press(&mut normal_mode(), keys, &mut buffer, &mut cursor);
+        (buffer, cursor)
+    }
+
+    #[test]
+    fn word_motions() {
+        let text = "foo bar.baz qux";
+        assert_eq!(run("w", text, 0).1, 4);
+        assert_eq!(run("ww", text, 0).1, 7);
+        assert_eq!(run("WW", text, 0).1, 12);
+        assert_eq!(run("e", text, 0).1, 2);
+        assert_eq!(run("ee", text, 0).1, 6);
+        assert_eq!(run("$b", text, 0).1, 12);
+        assert_eq!(run("$bb", text, 0).1, 8);
+        assert_eq!(run("$B", text, 0).1, 12);
+        assert_eq!(run("$BB", text, 0).1, 4);
+    }
+
+    #[test]
+    fn text_objects() {
+        assert_eq!(run("diw", "foo bar baz", 5), (String::from("foo  baz"), 4));
+        assert_eq!(run("daw", "foo bar baz", 5), (String::from("foo baz"), 4));
+        assert_eq!(run("di\"", "say \"hi there\" now", 0).0, "say \"\" now");
+        assert_eq!(run("da(", "f(a, (b)) c", 3).0, "f c");
+        assert_eq!(run("di(", "f(a, (b)) c", 6).0, "f(a, ()) c");
+    }
+
+    #[test]
+    fn change_word_stops_at_the_end_of_the_word() {
+        let mut editor = normal_mode();
+        let mut buffer = String::from("foo bar");
+        let mut cursor = 0;
+        press(&mut editor, "cw", &mut buffer, &mut cursor);
+        assert_eq!((buffer.as_str(), cursor), (" bar", 0));
+        assert_eq!(editor.mode(), Mode::Insert);
+        assert_eq!(run("dw", "foo bar", 0).0, "bar");
+    }
+
+    #[test]
+    fn counts() {
+        assert_eq!(run("3x", "abcdef", 0).0, "def");
+        assert_eq!(run("2dw", "a b c d", 0).0, "c d");
+        assert_eq!(run("d2w", "a b c d", 0).0, "c d");
+        assert_eq!(run("10l", "abc", 0).1, 2);
+    }
+
+    #[test]
+    fn huge_counts_are_clamped() {
+        let mut editor = normal_mode();
+        let mut buffer = String::from("a");
+        let mut cursor = 0;
+        press(&mut editor, "yl", &mut buffer, &mut cursor);
+        press(&mut editor, &"9".repeat(30), &mut buffer, &mut cursor);
+        assert_eq!(editor.count, Some(MAX_COUNT));
+        press(&mut editor, "p", &mut buffer, &mut cursor);
+        assert_eq!(buffer.len(), 1 + MAX_COUNT);
+    }
+
+    #[test]
+    fn undo() {
+        let mut editor = normal_mode();
+        let mut buffer = String::from("abc def");
+        let mut cursor = 0;
+        press(&mut editor, "xx", &mut buffer, &mut cursor);
+        assert_eq!(buffer, "c def");
+        press(&mut editor, "u", &mut buffer, &mut cursor);
+        assert_eq!(buffer, "bc def");
+        press(&mut editor, "cw", &mut buffer, &mut cursor);
+        editor.handle_key(Key::Esc, &mut buffer, &mut cursor);
+        assert_eq!(buffer, " def");
+        press(&mut editor, "uu", &mut buffer, &mut cursor);
+        assert_eq!((buffer.as_str(), cursor), ("abc def", 0));
+        press(&mut editor, "u", &mut buffer, &mut cursor);
+        assert_eq!(buffer, "abc def");
+    }
+}